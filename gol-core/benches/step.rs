@@ -0,0 +1,71 @@
+//! Compares the generation step of `GameGrid` and of the bit-packed `BitGrid`.
+//!
+//! Run on the host with `cargo bench-host`.
+#![feature(test)]
+
+extern crate test;
+
+use gol_core::bit_grid::{BitGrid, Word};
+use gol_core::game_grid::GameGrid;
+use gol_core::rng::XorShift32;
+use gol_core::topology::Topology;
+use test::Bencher;
+
+/// Generations computed per iteration.
+const GENERATIONS: usize = 10;
+
+fn bench_game_grid<const W: usize, const H: usize>(b: &mut Bencher) {
+    let mut start = GameGrid::<W, H>::default();
+    start.set_topology(Topology::Torus);
+    start.randomize_with(&mut XorShift32::new(0x2545_F491), 0.42);
+    b.iter(|| {
+        let mut gg = start.clone();
+        for _ in 0..GENERATIONS {
+            gg.update();
+        }
+        gg
+    });
+}
+
+fn bench_bit_grid<const W: usize, const H: usize, R: Word>(b: &mut Bencher) {
+    let mut start = BitGrid::<W, H, R>::default();
+    start.set_topology(Topology::Torus);
+    start.randomize_with(&mut XorShift32::new(0x2545_F491), 0.42);
+    b.iter(|| {
+        let mut grid = start.clone();
+        for _ in 0..GENERATIONS {
+            grid.update();
+        }
+        grid
+    });
+}
+
+#[bench]
+fn game_grid_17x8(b: &mut Bencher) {
+    bench_game_grid::<17, 8>(b);
+}
+
+#[bench]
+fn bit_grid_17x8(b: &mut Bencher) {
+    bench_bit_grid::<17, 8, u32>(b);
+}
+
+#[bench]
+fn game_grid_32x32(b: &mut Bencher) {
+    bench_game_grid::<32, 32>(b);
+}
+
+#[bench]
+fn bit_grid_32x32(b: &mut Bencher) {
+    bench_bit_grid::<32, 32, u32>(b);
+}
+
+#[bench]
+fn game_grid_64x64(b: &mut Bencher) {
+    bench_game_grid::<64, 64>(b);
+}
+
+#[bench]
+fn bit_grid_64x64(b: &mut Bencher) {
+    bench_bit_grid::<64, 64, u64>(b);
+}
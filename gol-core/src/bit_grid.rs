@@ -0,0 +1,415 @@
+use core::ops::{BitAnd, BitOr, BitXor, Not, Shl, Shr};
+
+use rand_core::RngCore;
+
+use crate::game_grid::{GameGrid, UpdateOutcome};
+use crate::hash::hash_array;
+use crate::rule::Rule;
+use crate::topology::Topology;
+
+/// An unsigned integer holding one row of a `BitGrid`, bit `x` being the cell in column `x`.
+pub trait Word:
+    Copy
+    + PartialEq
+    + BitAnd<Output = Self>
+    + BitOr<Output = Self>
+    + BitXor<Output = Self>
+    + Not<Output = Self>
+    + Shl<u32, Output = Self>
+    + Shr<u32, Output = Self>
+    + core::hash::Hash
+{
+    /// The number of bits, i.e. the widest row the word can hold.
+    const BITS: u32;
+    /// A word with no bit set.
+    const ZERO: Self;
+    /// A word with only bit 0 set.
+    const ONE: Self;
+
+    /// Reverses the order of all the bits of the word.
+    fn reverse_bits(self) -> Self;
+
+    /// Returns the number of bits set.
+    fn count_ones(self) -> u32;
+}
+
+macro_rules! impl_word {
+    ($($t:ty),*) => {
+        $(
+            impl Word for $t {
+                const BITS: u32 = <$t>::BITS;
+                const ZERO: Self = 0;
+                const ONE: Self = 1;
+
+                fn reverse_bits(self) -> Self {
+                    <$t>::reverse_bits(self)
+                }
+
+                fn count_ones(self) -> u32 {
+                    <$t>::count_ones(self)
+                }
+            }
+        )*
+    };
+}
+
+impl_word!(u8, u16, u32, u64);
+
+/// A game grid packing each row in a single word, updated with bitwise operations on whole rows.
+///
+/// It behaves like `GameGrid` but computes the 8 neighbor counts of a full row at once, with a bit-sliced adder,
+/// instead of looking up every neighbor of every cell. `W` must fit in the word type `R` (checked at compile time):
+/// `u32` is the native word of the Cortex-M0+, `u64` allows rows up to 64 cells.
+#[derive(Clone)]
+pub struct BitGrid<const W: usize, const H: usize, R: Word = u32> {
+    /// Rows of the game grid, from top to bottom.
+    rows: [R; H],
+    /// How the edges of the grid are glued together.
+    topology: Topology,
+}
+
+impl<const W: usize, const H: usize, R: Word> BitGrid<W, H, R> {
+    /// The number of cells of the game grid.
+    pub const CELLS: usize = W * H;
+
+    /// Compile-time check that a row fits in a word.
+    const ROW_FITS: () = assert!(
+        W > 0 && W as u32 <= R::BITS,
+        "the grid width must fit in the row word"
+    );
+
+    /// Creates a new game grid with all cells dead and bounded edges.
+    pub fn new() -> Self {
+        #[allow(clippy::let_unit_value)]
+        let () = Self::ROW_FITS;
+        BitGrid {
+            rows: [R::ZERO; H],
+            topology: Topology::default(),
+        }
+    }
+
+    /// Updates the game grid with Conway's rule (`B3/S23`).
+    ///
+    /// # Returns
+    ///
+    /// What changed between the previous and the new generation.
+    pub fn update(&mut self) -> UpdateOutcome {
+        self.update_with(&Rule::CONWAY)
+    }
+
+    /// Updates the game grid with the given rule.
+    ///
+    /// # Arguments
+    ///
+    /// * `rule`: The birth/survival rule to apply.
+    ///
+    /// # Returns
+    ///
+    /// What changed between the previous and the new generation.
+    pub fn update_with(&mut self, rule: &Rule) -> UpdateOutcome {
+        let mut outcome = UpdateOutcome::default();
+        let mut new_rows = [R::ZERO; H];
+        (0..H).for_each(|y| {
+            let row = self.rows[y];
+            let above = self.row_above(y);
+            let below = self.row_below(y);
+
+            // Bit-sliced counter: bit `x` of `count[i]` is bit `i` of the number of alive neighbors of cell `x`.
+            let mut count = [R::ZERO; 4];
+            for neighbors in [
+                self.west(above),
+                above,
+                self.east(above),
+                self.west(row),
+                self.east(row),
+                self.west(below),
+                below,
+                self.east(below),
+            ] {
+                let mut carry = neighbors;
+                for bit in count.iter_mut() {
+                    let next_carry = *bit & carry;
+                    *bit = *bit ^ carry;
+                    carry = next_carry;
+                }
+            }
+
+            let mut next = R::ZERO;
+            for n in 0..=8u8 {
+                let survives = rule.next_state(true, n);
+                let is_born = rule.next_state(false, n);
+                if !survives && !is_born {
+                    continue;
+                }
+                let mut with_n = !R::ZERO;
+                for (i, bit) in count.iter().enumerate() {
+                    with_n = with_n & if n & (1 << i) != 0 { *bit } else { !*bit };
+                }
+                if survives {
+                    next = next | (row & with_n);
+                }
+                if is_born {
+                    next = next | (!row & with_n);
+                }
+            }
+            let next = next & Self::row_mask();
+
+            outcome.births += (next & !row).count_ones() as usize;
+            outcome.deaths += (row & !next).count_ones() as usize;
+            outcome.population += next.count_ones() as usize;
+            new_rows[y] = next;
+        });
+
+        self.rows = new_rows;
+        outcome.changed = outcome.births + outcome.deaths > 0;
+        outcome
+    }
+
+    /// Randomizes the game grid using the provided random number generator.
+    ///
+    /// Given the same random numbers, the cells are the same as with `GameGrid::randomize_with`.
+    ///
+    /// # Arguments
+    ///
+    /// * `rng`: The random number generator to draw the cells from.
+    /// * `probability_to_live`: The probability for each cell to be alive.
+    pub fn randomize_with<G: RngCore>(&mut self, rng: &mut G, probability_to_live: f32) {
+        let mut random: [u8; W] = [0; W];
+        let thresh = probability_to_live * u8::MAX as f32;
+        (0..H).for_each(|y| {
+            rng.fill_bytes(&mut random);
+            let mut row = R::ZERO;
+            (0..W).for_each(|x| {
+                if random[x] < thresh as u8 {
+                    row = row | (R::ONE << x as u32);
+                }
+            });
+            self.rows[y] = row;
+        });
+    }
+
+    /// Computes the hash of the game grid.
+    ///
+    /// The hash only depends on the cells, but is not comparable with the one of a `GameGrid`.
+    pub fn get_hash(&self) -> u64 {
+        hash_array(&self.rows)
+    }
+
+    /// Returns the state of the cell at `(x, y)` position.
+    pub fn is_alive(&self, x: usize, y: usize) -> bool {
+        assert!(x < W);
+        self.rows[y] & (R::ONE << x as u32) != R::ZERO
+    }
+
+    /// Sets the state of the cell at `(x, y)` position.
+    pub fn set_alive(&mut self, x: usize, y: usize, alive: bool) {
+        assert!(x < W);
+        let bit = R::ONE << x as u32;
+        self.rows[y] = if alive {
+            self.rows[y] | bit
+        } else {
+            self.rows[y] & !bit
+        };
+    }
+
+    /// Returns the row at `y` position, bit `x` being the cell in column `x`.
+    pub fn row(&self, y: usize) -> R {
+        self.rows[y]
+    }
+
+    /// Iterates over the cells of the game grid, in row-major order.
+    pub fn cells(&self) -> impl Iterator<Item = bool> + '_ {
+        (0..H).flat_map(move |y| (0..W).map(move |x| self.is_alive(x, y)))
+    }
+
+    /// Returns the topology of the game grid.
+    pub fn topology(&self) -> Topology {
+        self.topology
+    }
+
+    /// Sets the topology of the game grid, used by the next updates.
+    ///
+    /// # Arguments
+    ///
+    /// * `topology`: How the edges of the grid are glued together.
+    pub fn set_topology(&mut self, topology: Topology) {
+        self.topology = topology;
+    }
+
+    /// A word with the `W` lowest bits set.
+    fn row_mask() -> R {
+        !(!R::ZERO << (W as u32 - 1) << 1)
+    }
+
+    /// Reverses the `W` cells of a row.
+    fn mirror_row(row: R) -> R {
+        row.reverse_bits() >> (R::BITS - W as u32)
+    }
+
+    /// Returns, for each column `x`, the cell in column `x - 1` of `row`.
+    fn west(&self, row: R) -> R {
+        let outside = match self.topology {
+            Topology::Bounded => R::ZERO,
+            Topology::Torus | Topology::KleinBottle | Topology::Cylinder => {
+                (row >> (W as u32 - 1)) & R::ONE
+            }
+            Topology::Mirror => row & R::ONE,
+        };
+        ((row << 1) | outside) & Self::row_mask()
+    }
+
+    /// Returns, for each column `x`, the cell in column `x + 1` of `row`.
+    fn east(&self, row: R) -> R {
+        let outside = match self.topology {
+            Topology::Bounded => R::ZERO,
+            Topology::Torus | Topology::KleinBottle | Topology::Cylinder => row & R::ONE,
+            Topology::Mirror => (row >> (W as u32 - 1)) & R::ONE,
+        };
+        (row >> 1) | (outside << (W as u32 - 1))
+    }
+
+    /// Returns the row above the row at `y` position.
+    fn row_above(&self, y: usize) -> R {
+        if y > 0 {
+            return self.rows[y - 1];
+        }
+        match self.topology {
+            Topology::Bounded | Topology::Cylinder => R::ZERO,
+            Topology::Torus => self.rows[H - 1],
+            Topology::KleinBottle => Self::mirror_row(self.rows[H - 1]),
+            Topology::Mirror => self.rows[0],
+        }
+    }
+
+    /// Returns the row below the row at `y` position.
+    fn row_below(&self, y: usize) -> R {
+        if y + 1 < H {
+            return self.rows[y + 1];
+        }
+        match self.topology {
+            Topology::Bounded | Topology::Cylinder => R::ZERO,
+            Topology::Torus => self.rows[0],
+            Topology::KleinBottle => Self::mirror_row(self.rows[0]),
+            Topology::Mirror => self.rows[H - 1],
+        }
+    }
+}
+
+impl<const W: usize, const H: usize, R: Word> Default for BitGrid<W, H, R> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const W: usize, const H: usize, R: Word> From<&GameGrid<W, H>> for BitGrid<W, H, R> {
+    /// Packs the cells and the topology of a `GameGrid`.
+    fn from(gg: &GameGrid<W, H>) -> Self {
+        let mut grid = Self::new();
+        grid.set_topology(gg.topology());
+        (0..H).for_each(|y| {
+            (0..W).for_each(|x| grid.set_alive(x, y, gg.is_alive(x, y)));
+        });
+        grid
+    }
+}
+
+impl<const W: usize, const H: usize, R: Word> From<&BitGrid<W, H, R>> for GameGrid<W, H> {
+    /// Unpacks the cells and the topology of a `BitGrid`.
+    fn from(grid: &BitGrid<W, H, R>) -> Self {
+        let mut gg = GameGrid::default();
+        gg.set_topology(grid.topology());
+        (0..H).for_each(|y| {
+            (0..W).for_each(|x| gg.set_alive(x, y, grid.is_alive(x, y)));
+        });
+        gg
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rng::XorShift32;
+
+    const TOPOLOGIES: [Topology; 5] = [
+        Topology::Bounded,
+        Topology::Torus,
+        Topology::KleinBottle,
+        Topology::Cylinder,
+        Topology::Mirror,
+    ];
+
+    /// Runs both implementations side by side and checks they stay identical.
+    fn assert_same_evolution<const W: usize, const H: usize, R: Word>(
+        rule: Rule,
+        generations: usize,
+    ) {
+        for topology in TOPOLOGIES {
+            for seed in 0..4 {
+                let mut gg = GameGrid::<W, H>::default();
+                gg.set_topology(topology);
+                gg.randomize_with(&mut XorShift32::new(seed), 0.4);
+                let mut grid = BitGrid::<W, H, R>::default();
+                grid.set_topology(topology);
+                grid.randomize_with(&mut XorShift32::new(seed), 0.4);
+                assert!(gg.cells().eq(grid.cells()));
+
+                for generation in 0..generations {
+                    let expected = gg.update_with(&rule);
+                    let outcome = grid.update_with(&rule);
+                    assert_eq!(
+                        outcome, expected,
+                        "{:?} seed {} generation {}",
+                        topology, seed, generation
+                    );
+                    assert!(
+                        gg.cells().eq(grid.cells()),
+                        "{:?} seed {} generation {}",
+                        topology,
+                        seed,
+                        generation
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn matches_game_grid_with_conway() {
+        assert_same_evolution::<17, 8, u32>(Rule::CONWAY, 50);
+    }
+
+    #[test]
+    fn matches_game_grid_with_other_rules() {
+        assert_same_evolution::<17, 8, u32>(Rule::HIGHLIFE, 30);
+        assert_same_evolution::<17, 8, u32>(Rule::DAY_AND_NIGHT, 30);
+        assert_same_evolution::<17, 8, u32>(Rule::SEEDS, 30);
+    }
+
+    #[test]
+    fn matches_game_grid_when_rows_fill_the_word() {
+        assert_same_evolution::<8, 8, u8>(Rule::CONWAY, 30);
+        assert_same_evolution::<32, 8, u32>(Rule::CONWAY, 30);
+        assert_same_evolution::<64, 16, u64>(Rule::CONWAY, 30);
+    }
+
+    #[test]
+    fn matches_game_grid_on_tiny_grids() {
+        assert_same_evolution::<1, 1, u8>(Rule::CONWAY, 5);
+        assert_same_evolution::<2, 3, u8>(Rule::DAY_AND_NIGHT, 5);
+    }
+
+    #[test]
+    fn converts_from_and_to_game_grid() {
+        let mut gg = GameGrid::<17, 8>::default();
+        gg.set_topology(Topology::KleinBottle);
+        gg.randomize_with(&mut XorShift32::new(7), 0.5);
+        let grid = BitGrid::<17, 8, u32>::from(&gg);
+        assert_eq!(grid.topology(), Topology::KleinBottle);
+        assert_eq!(
+            grid.row(0).count_ones() as usize,
+            gg.row(0).iter().filter(|c| **c).count()
+        );
+        let back = GameGrid::from(&grid);
+        assert_eq!(back.get_hash(), gg.get_hash());
+    }
+}
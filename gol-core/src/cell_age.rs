@@ -0,0 +1,171 @@
+use crate::game_grid::{AssertCells, GameGrid};
+
+/// How long a cell has been in its current state.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum CellAge {
+    /// The cell is alive, `generations` being `0` right after its birth.
+    Alive {
+        /// Number of generations the cell survived, saturating at `u8::MAX`.
+        generations: u8,
+    },
+    /// The cell is dead, `generations` being `0` right after its death.
+    Dead {
+        /// Number of generations since the death of the cell, saturating at `u8::MAX`.
+        /// Cells that were never alive start saturated, so they leave no ghost trail.
+        generations: u8,
+    },
+}
+
+impl CellAge {
+    /// The age of a cell that was never alive.
+    pub const NEVER_ALIVE: CellAge = CellAge::Dead {
+        generations: u8::MAX,
+    };
+
+    /// Returns the age of the cell after one more generation.
+    ///
+    /// # Arguments
+    ///
+    /// * `alive`: The state of the cell in the new generation.
+    pub const fn next(self, alive: bool) -> CellAge {
+        match (self, alive) {
+            (CellAge::Alive { generations }, true) => CellAge::Alive {
+                generations: generations.saturating_add(1),
+            },
+            (CellAge::Dead { .. }, true) => CellAge::Alive { generations: 0 },
+            (CellAge::Alive { .. }, false) => CellAge::Dead { generations: 0 },
+            (CellAge::Dead { generations }, false) => CellAge::Dead {
+                generations: generations.saturating_add(1),
+            },
+        }
+    }
+}
+
+impl Default for CellAge {
+    fn default() -> Self {
+        Self::NEVER_ALIVE
+    }
+}
+
+/// Tracks the age of every cell of a `GameGrid`, to color cells after how long they have been alive or dead.
+///
+/// Tracking is optional: a grid that is never observed does not pay for it.
+#[derive(Clone)]
+pub struct CellAges<const W: usize, const H: usize> {
+    ages: [[CellAge; W]; H],
+}
+
+impl<const W: usize, const H: usize> CellAges<W, H> {
+    /// Creates a tracker where no cell was ever alive.
+    pub const fn new() -> Self {
+        CellAges {
+            ages: [[CellAge::NEVER_ALIVE; W]; H],
+        }
+    }
+
+    /// Ages every cell by one generation, to be called after each update of the grid.
+    ///
+    /// # Arguments
+    ///
+    /// * `gg`: The game grid, in its new generation.
+    pub fn observe(&mut self, gg: &GameGrid<W, H>) {
+        (0..H).for_each(|y| {
+            (0..W).for_each(|x| {
+                self.ages[y][x] = self.ages[y][x].next(gg.is_alive(x, y));
+            });
+        });
+    }
+
+    /// Forgets every age, e.g. when the grid is randomized again: the next observed alive cells are newborns.
+    pub fn reset(&mut self) {
+        self.ages = [[CellAge::NEVER_ALIVE; W]; H];
+    }
+
+    /// Returns the age of the cell at `(x, y)` position.
+    pub fn get(&self, x: usize, y: usize) -> CellAge {
+        self.ages[y][x]
+    }
+
+    /// Converts the ages to an array, in the row-major order of the game grid.
+    ///
+    /// The length `N` of the array must be equal to `W * H`, this is checked at compile time.
+    pub fn to_array<const N: usize>(&self) -> [CellAge; N] {
+        #[allow(clippy::let_unit_value)]
+        let () = AssertCells::<W, H, N>::OK;
+        let mut array = [CellAge::NEVER_ALIVE; N];
+        (0..H).for_each(|y| {
+            (0..W).for_each(|x| {
+                array[y * W + x] = self.ages[y][x];
+            });
+        });
+        array
+    }
+}
+
+impl<const W: usize, const H: usize> Default for CellAges<W, H> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn blinker_ages() {
+        let mut gg = GameGrid::<5, 5>::default();
+        for x in 1..4 {
+            gg.set_alive(x, 2, true);
+        }
+        let mut ages = CellAges::new();
+        ages.observe(&gg);
+        assert_eq!(ages.get(2, 2), CellAge::Alive { generations: 0 });
+        assert_eq!(ages.get(2, 1), CellAge::NEVER_ALIVE);
+
+        gg.update();
+        ages.observe(&gg);
+        // The center survives, the ends die and the cells above and below are born.
+        assert_eq!(ages.get(2, 2), CellAge::Alive { generations: 1 });
+        assert_eq!(ages.get(1, 2), CellAge::Dead { generations: 0 });
+        assert_eq!(ages.get(2, 1), CellAge::Alive { generations: 0 });
+
+        gg.update();
+        ages.observe(&gg);
+        assert_eq!(ages.get(2, 2), CellAge::Alive { generations: 2 });
+        assert_eq!(ages.get(1, 2), CellAge::Alive { generations: 0 });
+        assert_eq!(ages.get(2, 1), CellAge::Dead { generations: 0 });
+        assert_eq!(ages.get(0, 0), CellAge::NEVER_ALIVE);
+
+        ages.reset();
+        assert_eq!(ages.get(2, 2), CellAge::NEVER_ALIVE);
+    }
+
+    #[test]
+    fn ages_saturate() {
+        let mut age = CellAge::Alive { generations: 0 };
+        for _ in 0..300 {
+            age = age.next(true);
+        }
+        assert_eq!(
+            age,
+            CellAge::Alive {
+                generations: u8::MAX
+            }
+        );
+        assert_eq!(age.next(false), CellAge::Dead { generations: 0 });
+        assert_eq!(CellAge::NEVER_ALIVE.next(false), CellAge::NEVER_ALIVE);
+    }
+
+    #[test]
+    fn to_array_is_row_major() {
+        let mut gg = GameGrid::<3, 2>::default();
+        gg.set_alive(2, 1, true);
+        let mut ages = CellAges::new();
+        ages.observe(&gg);
+        let array: [CellAge; 6] = ages.to_array();
+        assert_eq!(array[5], CellAge::Alive { generations: 0 });
+        assert!(array[..5].iter().all(|&age| age == CellAge::NEVER_ALIVE));
+    }
+}
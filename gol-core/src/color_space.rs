@@ -0,0 +1,360 @@
+use cichlid::ColorRGB;
+
+use crate::output::{Gamma, GammaTable};
+use crate::palette::mix;
+
+/// The number of hue units in a sector of the color wheel, between two primary and secondary colors.
+const SECTOR: i32 = 256;
+/// The number of hue units in a full turn of the color wheel.
+const TURN: i32 = 6 * SECTOR;
+
+/// `1.0` in the 12.20 fixed point of the Oklab computations.
+const ONE: i64 = 1 << 20;
+
+/// The sRGB transfer curve, to convert the channels to linear light before going to Oklab.
+static SRGB: GammaTable = GammaTable::new(Gamma::SRGB.r);
+
+/// Linear RGB to cone responses, see https://bottosson.github.io/posts/oklab/.
+const RGB_TO_LMS: [[i64; 3]; 3] = [
+    [432246, 562385, 53945],
+    [222197, 713765, 112614],
+    [92592, 295404, 660581],
+];
+const LMS_TO_LAB: [[i64; 3]; 3] = [
+    [220677, 832169, -4270],
+    [2074082, -2546563, 472482],
+    [27162, 820796, -847958],
+];
+const LAB_TO_LMS: [[i64; 3]; 3] = [
+    [1048576, 415590, 226287],
+    [1048576, -110689, -66956],
+    [1048576, -93831, -1354221],
+];
+const LMS_TO_RGB: [[i64; 3]; 3] = [
+    [4274773, -3468387, 242190],
+    [-1330054, 2736529, -357899],
+    [-4400, -737588, 1790564],
+];
+
+/// The color space two colors are interpolated in.
+///
+/// Every interpolation uses integer math only, the RP2040 having no FPU. Oklab is by far the most expensive, as every
+/// LED is interpolated at every frame.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum ColorSpace {
+    /// Each channel is interpolated on its own, which may go through dull colors between two hues.
+    #[default]
+    Rgb,
+    /// Hue, saturation and value, the hue taking the shortest way around the color wheel.
+    Hsv,
+    /// Hue, saturation and lightness, the hue taking the shortest way around the color wheel.
+    Hsl,
+    /// A perceptual color space, steps of the interpolation looking evenly spaced.
+    Oklab,
+}
+
+impl ColorSpace {
+    /// Every color space.
+    pub const ALL: [ColorSpace; 4] = [
+        ColorSpace::Rgb,
+        ColorSpace::Hsv,
+        ColorSpace::Hsl,
+        ColorSpace::Oklab,
+    ];
+
+    /// Mixes two colors, `amount` going from `0` (only `from`) to `255` (only `to`).
+    ///
+    /// Black, white and grays have no hue: in HSV and HSL, they take the hue of the other color, so a fade to black
+    /// keeps the hue of the color fading out.
+    pub fn mix(self, from: ColorRGB, to: ColorRGB, amount: u8) -> ColorRGB {
+        match amount {
+            0 => return from,
+            u8::MAX => return to,
+            _ => {}
+        }
+        match self {
+            ColorSpace::Rgb => mix(from, to, amount),
+            ColorSpace::Hsv => {
+                let (from, to) = hue_pair(to_hsv(from), to_hsv(to));
+                from_hsv(mix_hue(from, to, amount))
+            }
+            ColorSpace::Hsl => {
+                let (from, to) = hue_pair(to_hsl(from), to_hsl(to));
+                from_hsl(mix_hue(from, to, amount))
+            }
+            ColorSpace::Oklab => {
+                let (from, to) = (to_oklab(from), to_oklab(to));
+                let channel = |a: i64, b: i64| a + (b - a) * amount as i64 / u8::MAX as i64;
+                from_oklab([
+                    channel(from[0], to[0]),
+                    channel(from[1], to[1]),
+                    channel(from[2], to[2]),
+                ])
+            }
+        }
+    }
+}
+
+/// A color as a hue, from `0` to `TURN`, a saturation and a value or a lightness, both from `0` to `255`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct HueColor {
+    hue: i32,
+    saturation: i32,
+    level: i32,
+}
+
+/// Gives the hue of each color to the other one when it has none.
+fn hue_pair(mut from: HueColor, mut to: HueColor) -> (HueColor, HueColor) {
+    let has_hue = |c: &HueColor| c.saturation > 0 && c.level > 0;
+    if !has_hue(&from) {
+        from.hue = to.hue;
+        if from.level == 0 {
+            from.saturation = to.saturation;
+        }
+    } else if !has_hue(&to) {
+        to.hue = from.hue;
+        if to.level == 0 {
+            to.saturation = from.saturation;
+        }
+    }
+    (from, to)
+}
+
+/// Interpolates two colors, the hue taking the shortest arc.
+fn mix_hue(from: HueColor, to: HueColor, amount: u8) -> HueColor {
+    let lerp = |a: i32, b: i32| a + (b - a) * amount as i32 / u8::MAX as i32;
+    let mut arc = to.hue - from.hue;
+    if arc > TURN / 2 {
+        arc -= TURN;
+    } else if arc < -TURN / 2 {
+        arc += TURN;
+    }
+    HueColor {
+        hue: (from.hue + arc * amount as i32 / u8::MAX as i32).rem_euclid(TURN),
+        saturation: lerp(from.saturation, to.saturation),
+        level: lerp(from.level, to.level),
+    }
+}
+
+/// Returns the hue of a color and its chroma, i.e. the difference between its largest and smallest channels.
+fn hue(color: ColorRGB) -> (i32, i32) {
+    let (r, g, b) = (color.r as i32, color.g as i32, color.b as i32);
+    let (max, min) = (r.max(g).max(b), r.min(g).min(b));
+    let chroma = max - min;
+    let hue = if chroma == 0 {
+        0
+    } else if max == r {
+        SECTOR * (g - b) / chroma
+    } else if max == g {
+        2 * SECTOR + SECTOR * (b - r) / chroma
+    } else {
+        4 * SECTOR + SECTOR * (r - g) / chroma
+    };
+    (hue.rem_euclid(TURN), chroma)
+}
+
+/// Returns the red, green and blue parts of a hue, as many chromas out of `255`.
+fn hue_channels(hue: i32) -> [i32; 3] {
+    let (sector, position) = (hue / SECTOR, hue % SECTOR * 255 / (SECTOR - 1));
+    let (rising, falling) = (position, 255 - position);
+    match sector {
+        0 => [255, rising, 0],
+        1 => [falling, 255, 0],
+        2 => [0, 255, rising],
+        3 => [0, falling, 255],
+        4 => [rising, 0, 255],
+        _ => [255, 0, falling],
+    }
+}
+
+fn to_hsv(color: ColorRGB) -> HueColor {
+    let (hue, chroma) = hue(color);
+    let value = color.r.max(color.g).max(color.b) as i32;
+    HueColor {
+        hue,
+        saturation: if value == 0 { 0 } else { chroma * 255 / value },
+        level: value,
+    }
+}
+
+fn from_hsv(color: HueColor) -> ColorRGB {
+    let chroma = color.level * color.saturation / 255;
+    let min = color.level - chroma;
+    from_channels(hue_channels(color.hue).map(|c| min + chroma * c / 255))
+}
+
+fn to_hsl(color: ColorRGB) -> HueColor {
+    let (hue, chroma) = hue(color);
+    let max = color.r.max(color.g).max(color.b) as i32;
+    let min = color.r.min(color.g).min(color.b) as i32;
+    let spread = 255 - (max + min - 255).abs();
+    HueColor {
+        hue,
+        saturation: if spread == 0 {
+            0
+        } else {
+            chroma * 255 / spread
+        },
+        level: (max + min) / 2,
+    }
+}
+
+fn from_hsl(color: HueColor) -> ColorRGB {
+    let chroma = (255 - (2 * color.level - 255).abs()) * color.saturation / 255;
+    let min = color.level - chroma / 2;
+    from_channels(hue_channels(color.hue).map(|c| min + chroma * c / 255))
+}
+
+fn from_channels([r, g, b]: [i32; 3]) -> ColorRGB {
+    ColorRGB {
+        r: r.clamp(0, 255) as u8,
+        g: g.clamp(0, 255) as u8,
+        b: b.clamp(0, 255) as u8,
+    }
+}
+
+/// Converts a color to Oklab, with `L`, `a` and `b` in 12.20 fixed point.
+fn to_oklab(color: ColorRGB) -> [i64; 3] {
+    let rgb = [color.r, color.g, color.b].map(|c| SRGB.level(c) as i64 * ONE / 0xFF00);
+    let lms = transform(&RGB_TO_LMS, rgb).map(cbrt);
+    transform(&LMS_TO_LAB, lms)
+}
+
+/// Converts a color from Oklab, colors out of the sRGB gamut being clamped.
+fn from_oklab(lab: [i64; 3]) -> ColorRGB {
+    let lms = transform(&LAB_TO_LMS, lab).map(|c| c * c / ONE * c / ONE);
+    let [r, g, b] = transform(&LMS_TO_RGB, lms).map(encode);
+    ColorRGB { r, g, b }
+}
+
+/// Multiplies a vector by a 12.20 fixed point matrix.
+fn transform(matrix: &[[i64; 3]; 3], v: [i64; 3]) -> [i64; 3] {
+    matrix.map(|row| (row[0] * v[0] + row[1] * v[1] + row[2] * v[2]) / ONE)
+}
+
+/// Computes the cube root of a 12.20 fixed point number, negative numbers giving `0`.
+fn cbrt(x: i64) -> i64 {
+    // cbrt(x / ONE) * ONE = cbrt(x * ONE²), searched bit by bit.
+    let target = x.max(0) as u64 * (ONE * ONE) as u64;
+    let mut root = 0u64;
+    let mut bit = 1 << 20;
+    while bit > 0 {
+        let candidate = root | bit;
+        if candidate * candidate * candidate <= target {
+            root = candidate;
+        }
+        bit >>= 1;
+    }
+    root as i64
+}
+
+/// Converts a 12.20 fixed point linear channel back to sRGB.
+fn encode(linear: i64) -> u8 {
+    let level = (linear.clamp(0, ONE) * 0xFF00 / ONE) as u16;
+    // The first channel whose level reaches `level`, then the closest of it and the previous one.
+    let mut channel = 0u8;
+    let mut bit = 0x80;
+    while bit > 0 {
+        if SRGB.level(channel | bit) < level {
+            channel |= bit;
+        }
+        bit >>= 1;
+    }
+    if SRGB.level(channel) < level && channel < u8::MAX {
+        channel += 1;
+    }
+    if channel > 0 && level - SRGB.level(channel - 1) < SRGB.level(channel).saturating_sub(level) {
+        channel - 1
+    } else {
+        channel
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BLUE_VIOLET: ColorRGB = ColorRGB {
+        r: 138,
+        g: 43,
+        b: 226,
+    };
+    const RED: ColorRGB = ColorRGB { r: 255, g: 0, b: 0 };
+    const BLUE: ColorRGB = ColorRGB { r: 0, g: 0, b: 255 };
+
+    fn close(a: ColorRGB, b: ColorRGB, tolerance: u8) -> bool {
+        a.r.abs_diff(b.r) <= tolerance
+            && a.g.abs_diff(b.g) <= tolerance
+            && a.b.abs_diff(b.b) <= tolerance
+    }
+
+    #[test]
+    fn endpoints_are_exact() {
+        for space in ColorSpace::ALL {
+            assert_eq!(space.mix(BLUE_VIOLET, RED, 0), BLUE_VIOLET, "{:?}", space);
+            assert_eq!(space.mix(BLUE_VIOLET, RED, 255), RED, "{:?}", space);
+        }
+    }
+
+    #[test]
+    fn conversions_round_trip() {
+        for r in (0..=255).step_by(15) {
+            for g in (0..=255).step_by(15) {
+                for b in (0..=255).step_by(15) {
+                    let color = ColorRGB { r, g, b };
+                    assert!(close(from_hsv(to_hsv(color)), color, 3), "{:?}", color);
+                    assert!(close(from_hsl(to_hsl(color)), color, 3), "{:?}", color);
+                    assert!(close(from_oklab(to_oklab(color)), color, 2), "{:?}", color);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn hue_takes_the_shortest_arc() {
+        // From red to blue, the short way goes through magenta rather than green.
+        for space in [ColorSpace::Hsv, ColorSpace::Hsl] {
+            let middle = space.mix(RED, BLUE, 128);
+            assert!(
+                middle.r > 200 && middle.b > 200 && middle.g < 8,
+                "{:?}",
+                middle
+            );
+        }
+        let muddy = ColorSpace::Rgb.mix(RED, BLUE, 128);
+        assert!(muddy.r < 130 && muddy.b < 130);
+    }
+
+    #[test]
+    fn fades_to_black_keep_their_hue() {
+        for space in [ColorSpace::Hsv, ColorSpace::Hsl] {
+            for amount in [64, 128, 192] {
+                let color = space.mix(BLUE_VIOLET, ColorRGB::Black, amount);
+                let (faded, _) = hue(color);
+                let (original, _) = hue(BLUE_VIOLET);
+                assert!(faded.abs_diff(original) < 24, "{:?} {:?}", space, color);
+            }
+        }
+    }
+
+    #[test]
+    fn oklab_is_perceptually_even() {
+        // Halfway between black and white in Oklab has half the lightness, a darker gray than the RGB halfway.
+        let middle = ColorSpace::Oklab.mix(ColorRGB::Black, ColorRGB::White, 128);
+        assert!(close(
+            middle,
+            ColorRGB {
+                r: middle.r,
+                g: middle.r,
+                b: middle.r
+            },
+            1
+        ));
+        assert!((90..=120).contains(&middle.r), "{:?}", middle);
+        assert_eq!(
+            ColorSpace::Rgb.mix(ColorRGB::Black, ColorRGB::White, 128).r,
+            128
+        );
+    }
+}
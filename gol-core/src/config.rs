@@ -0,0 +1,133 @@
+//! The configuration of the pico-game-of-light board: the grid, its ledstrip, the game and the animation.
+//!
+//! The firmware and the simulator both build on these constants, so the simulator shows what the ledstrip does.
+//! The settings of the physical LEDs only (gamma, dithering, power) stay in the firmware.
+
+use cichlid::ColorRGB;
+
+use crate::color_space::ColorSpace;
+use crate::easing::Easing;
+use crate::frame::FrameStyle;
+use crate::game::GameSettings;
+use crate::game_grid::GameGrid;
+use crate::idle::{IdleAnimation, IdleEffect};
+use crate::layout::{Padded, Serpentine, StripPadding};
+use crate::ledstrip_effect::{TransitionColorSpaces, TransitionEasing, TRANSITION_STEPS};
+use crate::palette::Palette;
+use crate::rule::Rule;
+use crate::stagnation::ReseedPolicy;
+use crate::timing::TransitionTiming;
+use crate::topology::Topology;
+
+/// The width of the game grid, in cells.
+pub const GRID_WIDTH: usize = 17;
+
+/// The height of the game grid, in cells.
+pub const GRID_HEIGHT: usize = 8;
+
+/// The number of LEDs of the physical strip.
+pub const LEDSTRIP_LENGTH: usize = 144;
+
+/// The game grid displayed on the ledstrip.
+pub type Board = GameGrid<GRID_WIDTH, GRID_HEIGHT>;
+
+/// The number of LEDs driven by the game grid.
+pub const NUM_LEDS: usize = Board::CELLS;
+
+/// Where the LEDs driven by the game grid sit on the ledstrip, the other ones stay blank.
+pub const LEDSTRIP_PADDING: StripPadding = StripPadding::new(LEDSTRIP_LENGTH);
+
+const _: () = assert!(
+    LEDSTRIP_PADDING.fits(NUM_LEDS),
+    "the game grid does not fit on the ledstrip"
+);
+
+/// The color of the LEDs of the ledstrip showing no cell.
+pub const PADDING_COLOR: ColorRGB = ColorRGB::Black;
+
+/// How the cells of the game grid are wired on the ledstrip.
+/// The strip zigzags along the rows, see the README schematic.
+pub const LED_LAYOUT: Padded<Serpentine> =
+    Padded::new(Serpentine::new(GRID_WIDTH, GRID_HEIGHT), LEDSTRIP_PADDING);
+
+/// If set to `true`, cells are colored after their age (see `Palette::color`) instead of their state only.
+pub const AGE_COLORING: bool = true;
+
+/// The palette at startup, see `Palette::ALL` for the built-in ones.
+pub const STARTUP_PALETTE: Palette<'static> = Palette::CLASSIC;
+
+/// The animation of the cells that keep their state, with periods in frames of `FRAME_INTERVAL_US`.
+pub const IDLE_ANIMATION: IdleAnimation = IdleAnimation {
+    alive: IdleEffect::Breathing {
+        period: 150,
+        depth: 96,
+    },
+    dead: IdleEffect::Shimmer {
+        color: ColorRGB { r: 0, g: 0, b: 64 },
+        period: 250,
+    },
+};
+
+/// The interval between two generations of the game grid, in µs.
+pub const GENERATION_INTERVAL_US: u64 = 5_000_000;
+
+/// The interval between two frames of the ledstrip, in µs.
+pub const FRAME_INTERVAL_US: u64 = 20_000;
+
+/// How long the births, deaths and age changes of the cells last, as a part of `GENERATION_INTERVAL_US`.
+pub const TRANSITION_TIMING: TransitionTiming = TransitionTiming::percent(20);
+
+/// How the births, deaths and age changes of the cells are eased over their transition.
+pub const TRANSITION_EASING: TransitionEasing = TransitionEasing {
+    birth: Easing::QuadIn,
+    death: Easing::QuadOut,
+    steady: Easing::Linear,
+};
+
+/// The color space the births, deaths and age changes of the cells are interpolated in.
+/// Births and deaths keep the hue of the cell while it fades in or out.
+pub const TRANSITION_COLOR_SPACES: TransitionColorSpaces = TransitionColorSpaces {
+    birth: ColorSpace::Hsv,
+    death: ColorSpace::Hsv,
+    steady: ColorSpace::Rgb,
+};
+
+/// How the frames of the ledstrip are drawn from the generations of the game grid.
+pub const FRAME_STYLE: FrameStyle = FrameStyle {
+    width: GRID_WIDTH,
+    age_coloring: AGE_COLORING,
+    easing: TRANSITION_EASING,
+    color_spaces: TRANSITION_COLOR_SPACES,
+    idle: IDLE_ANIMATION,
+};
+
+/// The number of animation frames over which a new palette is cross-faded in.
+pub const PALETTE_FADE_STEPS: usize = TRANSITION_STEPS;
+
+/// How the edges of the game grid are glued together.
+/// `Topology::Bounded` keeps the edges dead, wrapping ones such as `Topology::Torus` keep gliders alive on such a small board.
+pub const GRID_TOPOLOGY: Topology = Topology::Bounded;
+
+/// The number of past generations kept to detect oscillators, i.e. the longest detectable period.
+pub const HASH_HISTORY_LEN: usize = 32;
+
+/// When a still life or an oscillator is randomized again.
+pub const RESEED_POLICY: ReseedPolicy = ReseedPolicy {
+    still_life_repeats: 1,
+    oscillator_repeats: 3,
+};
+
+/// How the game runs.
+pub const GAME_SETTINGS: GameSettings = GameSettings {
+    topology: GRID_TOPOLOGY,
+    reseed_policy: RESEED_POLICY,
+};
+
+/// The rule of the automaton at startup, in B/S notation (e.g. "B36/S23" for HighLife).
+pub const STARTUP_RULESTRING: &str = "B3/S23";
+
+/// The rule of the automaton at startup, validated at compile time.
+pub const STARTUP_RULE: Rule = match Rule::parse(STARTUP_RULESTRING) {
+    Ok(rule) => rule,
+    Err(_) => panic!("STARTUP_RULESTRING is not a valid B/S rulestring"),
+};
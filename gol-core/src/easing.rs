@@ -0,0 +1,156 @@
+/// A quarter of a sine wave: `255 * sin(i * π / 128)` for `i` in `0..=64`.
+const QUARTER_SINE: [u8; 65] = [
+    0, 6, 13, 19, 25, 31, 37, 44, 50, 56, 62, 68, 74, 80, 86, 92, 98, 103, 109, 115, 120, 126, 131,
+    136, 142, 147, 152, 157, 162, 167, 171, 176, 180, 185, 189, 193, 197, 201, 205, 208, 212, 215,
+    219, 222, 225, 228, 231, 233, 236, 238, 240, 242, 244, 246, 247, 249, 250, 251, 252, 253, 254,
+    254, 255, 255, 255,
+];
+
+/// How the progress of a transition is distributed over its steps.
+///
+/// Progress is fixed point, from `0` at the start of the transition to `255` at its end.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Easing {
+    /// Constant speed.
+    #[default]
+    Linear,
+    /// Starts slowly, quadratic.
+    QuadIn,
+    /// Ends slowly, quadratic.
+    QuadOut,
+    /// Starts and ends slowly, quadratic.
+    QuadInOut,
+    /// Starts slowly, cubic.
+    CubicIn,
+    /// Ends slowly, cubic.
+    CubicOut,
+    /// Starts and ends slowly, cubic.
+    CubicInOut,
+    /// Starts and ends slowly along half a cosine.
+    SineInOut,
+    /// Starts very slowly, doubling its progress every tenth of the transition.
+    ExponentialIn,
+    /// Jumps from the start to the end half way through.
+    Step,
+}
+
+impl Easing {
+    /// Every easing curve.
+    pub const ALL: [Easing; 10] = [
+        Easing::Linear,
+        Easing::QuadIn,
+        Easing::QuadOut,
+        Easing::QuadInOut,
+        Easing::CubicIn,
+        Easing::CubicOut,
+        Easing::CubicInOut,
+        Easing::SineInOut,
+        Easing::ExponentialIn,
+        Easing::Step,
+    ];
+
+    /// Eases the progress of a transition.
+    ///
+    /// # Arguments
+    ///
+    /// * `t`: The elapsed part of the transition, from `0` to `255`.
+    ///
+    /// # Returns
+    ///
+    /// The eased progress, from `0` to `255`. Every curve starts at `0` and ends at `255`.
+    pub const fn ease(self, t: u8) -> u8 {
+        const MAX: u32 = u8::MAX as u32;
+        let t = t as u32;
+        let eased = match self {
+            Easing::Linear => t,
+            Easing::QuadIn => t * t / MAX,
+            Easing::QuadOut => MAX - (MAX - t) * (MAX - t) / MAX,
+            Easing::QuadInOut if t < 128 => 2 * t * t / MAX,
+            Easing::QuadInOut => MAX - 2 * (MAX - t) * (MAX - t) / MAX,
+            Easing::CubicIn => t * t * t / (MAX * MAX),
+            Easing::CubicOut => MAX - (MAX - t) * (MAX - t) * (MAX - t) / (MAX * MAX),
+            Easing::CubicInOut if t < 128 => 4 * t * t * t / (MAX * MAX),
+            Easing::CubicInOut => MAX - 4 * (MAX - t) * (MAX - t) * (MAX - t) / (MAX * MAX),
+            Easing::SineInOut => {
+                // (1 - cos(π t)) / 2 = sin²(π t / 2), the sine being interpolated in the quarter table.
+                let position = t * 64 * 256 / MAX;
+                let (i, fraction) = ((position >> 8) as usize, position & 0xFF);
+                let sine = if i + 1 < QUARTER_SINE.len() {
+                    let (a, b) = (QUARTER_SINE[i] as u32, QUARTER_SINE[i + 1] as u32);
+                    a + (b - a) * fraction / 256
+                } else {
+                    QUARTER_SINE[i] as u32
+                };
+                sine * sine / MAX
+            }
+            Easing::ExponentialIn if t == 0 => 0,
+            Easing::ExponentialIn => {
+                // 2^(10 t - 10), the fractional power of two being linearly approximated.
+                let exponent = 10 * t;
+                let (power, fraction) = (exponent / MAX, exponent % MAX);
+                ((MAX + fraction) << power) >> 10
+            }
+            Easing::Step if t < 128 => 0,
+            Easing::Step => MAX,
+        };
+        eased as u8
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn curves_start_at_zero_and_end_at_max() {
+        for easing in Easing::ALL {
+            assert_eq!(easing.ease(0), 0, "{:?}", easing);
+            assert_eq!(easing.ease(u8::MAX), u8::MAX, "{:?}", easing);
+        }
+    }
+
+    #[test]
+    fn curves_are_monotonic() {
+        for easing in Easing::ALL {
+            for t in 0..u8::MAX {
+                assert!(
+                    easing.ease(t) <= easing.ease(t + 1),
+                    "{:?} decreases at {}",
+                    easing,
+                    t
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn curves_have_their_shape() {
+        assert_eq!(Easing::Linear.ease(100), 100);
+        assert!(Easing::QuadIn.ease(64) < 64);
+        assert!(Easing::QuadOut.ease(64) > 64);
+        assert!(Easing::CubicIn.ease(128) < Easing::QuadIn.ease(128));
+        assert!(Easing::CubicOut.ease(128) > Easing::QuadOut.ease(128));
+        for easing in [Easing::QuadInOut, Easing::CubicInOut, Easing::SineInOut] {
+            assert!(easing.ease(32) < 32, "{:?}", easing);
+            assert!((126..=130).contains(&easing.ease(128)), "{:?}", easing);
+            assert!(easing.ease(224) > 224, "{:?}", easing);
+        }
+        assert!(Easing::ExponentialIn.ease(128) < 16);
+        assert_eq!(Easing::Step.ease(127), 0);
+        assert_eq!(Easing::Step.ease(128), u8::MAX);
+    }
+
+    #[test]
+    fn curves_span_the_whole_transition() {
+        for easing in Easing::ALL {
+            assert_eq!(easing.ease(0), 0, "{:?}", easing);
+            assert_eq!(easing.ease(u8::MAX), u8::MAX, "{:?}", easing);
+            assert!(
+                (0..u8::MAX).all(|t| easing.ease(t) <= easing.ease(t + 1)),
+                "{:?}",
+                easing
+            );
+        }
+    }
+}
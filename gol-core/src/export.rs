@@ -0,0 +1,260 @@
+use core::fmt::{self, Write};
+
+use crate::game_grid::GameGrid;
+use crate::rule::Rule;
+
+/// The longest line written in the body of an RLE pattern, as recommended by the format.
+const RLE_LINE_LENGTH: usize = 70;
+
+/// Writes a board as an RLE pattern, the format read by `Pattern::parse` and most Life tools.
+///
+/// Trailing dead cells of each row and trailing empty rows are left out, the header keeps the full board size.
+///
+/// # Arguments
+///
+/// * `gg`: The game grid to write.
+/// * `rule`: The rule written in the header.
+/// * `out`: Where to write the pattern, e.g. a `TextBuffer`.
+pub fn write_rle<const W: usize, const H: usize>(
+    gg: &GameGrid<W, H>,
+    rule: &Rule,
+    out: &mut impl Write,
+) -> fmt::Result {
+    writeln!(out, "x = {}, y = {}, rule = {}", W, H, rule)?;
+    let mut line_length = 0;
+    let mut pending_rows = 0;
+    for y in 0..H {
+        let row = gg.row(y);
+        let mut x = 0;
+        while x < W {
+            let alive = row[x];
+            let run = row[x..].iter().take_while(|&&a| a == alive).count();
+            x += run;
+            if !alive && x == W {
+                break;
+            }
+            if pending_rows > 0 {
+                write_run(out, &mut line_length, pending_rows, '$')?;
+                pending_rows = 0;
+            }
+            write_run(out, &mut line_length, run, if alive { 'o' } else { 'b' })?;
+        }
+        pending_rows += 1;
+    }
+    write_run(out, &mut line_length, 1, '!')?;
+    out.write_char('\n')
+}
+
+/// Writes a board as a plaintext (`.cells`) pattern, one line of `.` and `O` per row.
+///
+/// # Arguments
+///
+/// * `gg`: The game grid to write.
+/// * `out`: Where to write the pattern.
+pub fn write_plaintext<const W: usize, const H: usize>(
+    gg: &GameGrid<W, H>,
+    out: &mut impl Write,
+) -> fmt::Result {
+    for y in 0..H {
+        for &alive in gg.row(y) {
+            out.write_char(if alive { 'O' } else { '.' })?;
+        }
+        out.write_char('\n')?;
+    }
+    Ok(())
+}
+
+/// Writes a board as a Life 1.06 pattern, one line with the `x y` coordinates of each alive cell.
+///
+/// # Arguments
+///
+/// * `gg`: The game grid to write.
+/// * `out`: Where to write the pattern.
+pub fn write_life106<const W: usize, const H: usize>(
+    gg: &GameGrid<W, H>,
+    out: &mut impl Write,
+) -> fmt::Result {
+    out.write_str("#Life 1.06\n")?;
+    for y in 0..H {
+        for x in 0..W {
+            if gg.is_alive(x, y) {
+                writeln!(out, "{} {}", x, y)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Writes one `<count><tag>` item of an RLE body, starting a new line if it would be too long.
+fn write_run(
+    out: &mut impl Write,
+    line_length: &mut usize,
+    count: usize,
+    tag: char,
+) -> fmt::Result {
+    let mut digits = 0;
+    if count > 1 {
+        let mut rest = count;
+        while rest > 0 {
+            digits += 1;
+            rest /= 10;
+        }
+    }
+    if *line_length + digits + 1 > RLE_LINE_LENGTH {
+        out.write_char('\n')?;
+        *line_length = 0;
+    }
+    if count > 1 {
+        write!(out, "{}", count)?;
+    }
+    out.write_char(tag)?;
+    *line_length += digits + 1;
+    Ok(())
+}
+
+/// A fixed-capacity text buffer, to format a board without allocating before sending it over RTT.
+pub struct TextBuffer<const N: usize> {
+    bytes: [u8; N],
+    len: usize,
+}
+
+impl<const N: usize> TextBuffer<N> {
+    /// Creates an empty buffer.
+    pub const fn new() -> Self {
+        TextBuffer {
+            bytes: [0; N],
+            len: 0,
+        }
+    }
+
+    /// Returns the text written so far.
+    pub fn as_str(&self) -> &str {
+        // Only whole `str`s are copied in, the bytes are always valid UTF-8.
+        core::str::from_utf8(&self.bytes[..self.len]).unwrap_or_default()
+    }
+
+    /// Returns the length of the text written so far, in bytes.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if nothing has been written.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Forgets the text written so far.
+    pub fn clear(&mut self) {
+        self.len = 0;
+    }
+}
+
+impl<const N: usize> Default for TextBuffer<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize> Write for TextBuffer<N> {
+    /// Appends `s`, or fails without writing anything if it does not fit.
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let end = self.len + s.len();
+        if end > N {
+            return Err(fmt::Error);
+        }
+        self.bytes[self.len..end].copy_from_slice(s.as_bytes());
+        self.len = end;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pattern::{classics, Pattern, Transform};
+    use crate::rng::XorShift32;
+
+    type Grid = GameGrid<17, 8>;
+
+    fn random_grid(seed: u32) -> Grid {
+        let mut gg = Grid::default();
+        gg.randomize_with(&mut XorShift32::new(seed), 0.42);
+        gg
+    }
+
+    fn stamped(pattern: &Pattern, x: usize, y: usize) -> Grid {
+        let mut gg = Grid::default();
+        assert!(gg.stamp(pattern, x, y, Transform::Identity));
+        gg
+    }
+
+    #[test]
+    fn writes_glider_rle() {
+        let mut gg = Grid::default();
+        gg.stamp(&classics::GLIDER, 0, 1, Transform::Identity);
+        let mut text = String::new();
+        write_rle(&gg, &Rule::HIGHLIFE, &mut text).unwrap();
+        assert_eq!(text, "x = 17, y = 8, rule = B36/S23\n$bo$2bo$3o!\n");
+    }
+
+    #[test]
+    fn rle_round_trip() {
+        for seed in 1..20 {
+            let gg = random_grid(seed);
+            let mut text = TextBuffer::<512>::new();
+            write_rle(&gg, &Rule::DAY_AND_NIGHT, &mut text).unwrap();
+            assert!(text
+                .as_str()
+                .lines()
+                .all(|line| line.len() <= RLE_LINE_LENGTH));
+
+            let pattern = Pattern::parse(text.as_str()).unwrap();
+            assert_eq!((pattern.width(), pattern.height()), (17, 8));
+            assert_eq!(pattern.rule(), Some(Rule::DAY_AND_NIGHT));
+            assert_eq!(stamped(&pattern, 0, 0).get_hash(), gg.get_hash());
+        }
+    }
+
+    #[test]
+    fn plaintext_round_trip() {
+        for seed in 1..20 {
+            let gg = random_grid(seed);
+            let mut text = TextBuffer::<512>::new();
+            write_plaintext(&gg, &mut text).unwrap();
+            assert_eq!(text.len(), 18 * 8);
+
+            let pattern = Pattern::parse_plaintext(text.as_str()).unwrap();
+            assert_eq!((pattern.width(), pattern.height()), (17, 8));
+            assert_eq!(stamped(&pattern, 0, 0).get_hash(), gg.get_hash());
+        }
+    }
+
+    #[test]
+    fn life106_round_trip() {
+        for seed in 1..20 {
+            let gg = random_grid(seed);
+            let mut text = String::new();
+            write_life106(&gg, &mut text).unwrap();
+
+            let pattern = Pattern::parse_life106(&text).unwrap();
+            let (x, y) = pattern.offset();
+            assert_eq!(
+                stamped(&pattern, x as usize, y as usize).get_hash(),
+                gg.get_hash()
+            );
+        }
+    }
+
+    #[test]
+    fn text_buffer_rejects_overflow() {
+        let mut text = TextBuffer::<8>::new();
+        assert!(text.is_empty());
+        text.write_str("#Life").unwrap();
+        assert!(text.write_str(" 1.06").is_err());
+        assert_eq!(text.as_str(), "#Life");
+        assert!(write_life106(&random_grid(1), &mut text).is_err());
+
+        text.clear();
+        assert_eq!(text.as_str(), "");
+    }
+}
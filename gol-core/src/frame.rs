@@ -0,0 +1,196 @@
+use cichlid::ColorRGB;
+
+use crate::cell_age::CellAge;
+use crate::idle::IdleAnimation;
+use crate::ledstrip_effect::{TransitionColorSpaces, TransitionEasing};
+use crate::palette::CrossFade;
+
+/// The state of the cells of the game grid at a generation, in row-major order.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Generation<const N: usize> {
+    /// Whether each cell is alive.
+    pub alive: [bool; N],
+    /// The age of each cell.
+    pub ages: [CellAge; N],
+}
+
+impl<const N: usize> Generation<N> {
+    /// The generation before the first one, with every cell dead since ever.
+    pub const EMPTY: Generation<N> = Generation {
+        alive: [false; N],
+        ages: [CellAge::NEVER_ALIVE; N],
+    };
+}
+
+impl<const N: usize> Default for Generation<N> {
+    fn default() -> Self {
+        Self::EMPTY
+    }
+}
+
+/// The moment of a frame within the transition between two generations.
+#[derive(Clone, Copy)]
+pub struct FrameStep<'a> {
+    /// The progress of the transition, from `0` at its start to `255` once done, see `TransitionClock::progress`.
+    pub progress: u8,
+    /// Frames since startup, drives the idle animation.
+    pub tick: u32,
+    /// The palettes the cells are colored with, possibly cross-fading.
+    pub fade: &'a CrossFade<'a>,
+}
+
+/// How the frames of the ledstrip are drawn from the generations of the game grid.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FrameStyle {
+    /// The width of the game grid, in cells, which spreads the palette gradients over the columns.
+    pub width: usize,
+    /// If set to `true`, cells are colored after their age instead of their state only.
+    pub age_coloring: bool,
+    /// How the births, deaths and age changes of the cells are eased over their transition.
+    pub easing: TransitionEasing,
+    /// The color space the births, deaths and age changes of the cells are interpolated in.
+    pub color_spaces: TransitionColorSpaces,
+    /// The animation of the cells that keep their state.
+    pub idle: IdleAnimation,
+}
+
+impl FrameStyle {
+    /// Computes the colors of a frame of the transition from `prev` to `next`.
+    ///
+    /// The colors only depend on the arguments, so a sequence of frames can be replayed from a sequence of
+    /// generations, whatever drives the timing.
+    ///
+    /// # Arguments
+    ///
+    /// * `prev`: The generation the transition starts from.
+    /// * `next`: The generation the transition goes to.
+    /// * `step`: The moment of the frame.
+    ///
+    /// # Returns
+    ///
+    /// The color of each cell, in the row-major order of the game grid.
+    pub fn render_frame<const N: usize>(
+        &self,
+        prev: &Generation<N>,
+        next: &Generation<N>,
+        step: &FrameStep,
+    ) -> [ColorRGB; N] {
+        let mut colors = [ColorRGB::default(); N];
+        (0..N).for_each(|led| {
+            let column = led % self.width;
+            let (was_alive, alive) = (prev.alive[led], next.alive[led]);
+            colors[led] = step.fade.color(|palette| {
+                let (from, to) = if self.age_coloring {
+                    (
+                        palette.color(prev.ages[led], column, self.width),
+                        palette.color(next.ages[led], column, self.width),
+                    )
+                } else {
+                    (
+                        palette.state_color(was_alive, column, self.width),
+                        palette.state_color(alive, column, self.width),
+                    )
+                };
+                let amount = self.easing.progress(was_alive, alive, step.progress);
+                self.color_spaces.mix(from, to, was_alive, alive, amount)
+            });
+            if alive == was_alive {
+                colors[led] = self.idle.apply(colors[led], alive, led, step.tick);
+            }
+        });
+        colors
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::idle::IdleEffect;
+    use crate::palette::Palette;
+
+    const STYLE: FrameStyle = FrameStyle {
+        width: 4,
+        age_coloring: false,
+        easing: TransitionEasing::LINEAR,
+        color_spaces: TransitionColorSpaces::RGB,
+        idle: IdleAnimation::NONE,
+    };
+
+    fn generation(alive: [bool; 4]) -> Generation<4> {
+        Generation {
+            alive,
+            ages: [CellAge::NEVER_ALIVE; 4],
+        }
+    }
+
+    #[test]
+    fn transitions_go_from_the_previous_to_the_next_colors() {
+        let fade = CrossFade::new(Palette::CLASSIC, 1);
+        let (prev, next) = (
+            generation([false, true, false, true]),
+            generation([false, false, true, true]),
+        );
+        let frame = |progress| {
+            STYLE.render_frame(
+                &prev,
+                &next,
+                &FrameStep {
+                    progress,
+                    tick: 0,
+                    fade: &fade,
+                },
+            )
+        };
+        let state = |alive, column| Palette::CLASSIC.state_color(alive, column, 4);
+        assert_eq!(
+            frame(0),
+            [
+                state(false, 0),
+                state(true, 1),
+                state(false, 2),
+                state(true, 3)
+            ]
+        );
+        assert_eq!(
+            frame(u8::MAX),
+            [
+                state(false, 0),
+                state(false, 1),
+                state(true, 2),
+                state(true, 3)
+            ]
+        );
+    }
+
+    #[test]
+    fn only_steady_cells_are_animated() {
+        let fade = CrossFade::new(Palette::CLASSIC, 1);
+        let style = FrameStyle {
+            idle: IdleAnimation {
+                alive: IdleEffect::Breathing {
+                    period: 10,
+                    depth: 255,
+                },
+                dead: IdleEffect::None,
+            },
+            ..STYLE
+        };
+        let (prev, next) = (
+            generation([true, true, false, false]),
+            generation([true, false, true, false]),
+        );
+        let frames: [[ColorRGB; 4]; 2] = [0, 2].map(|tick| {
+            style.render_frame(
+                &prev,
+                &next,
+                &FrameStep {
+                    progress: u8::MAX,
+                    tick,
+                    fade: &fade,
+                },
+            )
+        });
+        assert_ne!(frames[0][0], frames[1][0]);
+        assert_eq!(frames[0][1..], frames[1][1..]);
+    }
+}
@@ -0,0 +1,150 @@
+use crate::cell_age::CellAges;
+use crate::frame::Generation;
+use crate::game_grid::{GameGrid, UpdateOutcome};
+use crate::rng::XorShift32;
+use crate::rule::Rule;
+use crate::stagnation::{ReseedPolicy, Stagnation, StagnationDetector};
+use crate::topology::Topology;
+
+/// The probability for each cell to be alive on the first board of a run.
+pub const STARTUP_DENSITY: f32 = 0.42;
+
+/// The probability for each cell to be alive when a stagnating board is randomized again.
+pub const RESEED_DENSITY: f32 = 0.3;
+
+/// How a `Game` runs.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct GameSettings {
+    /// How the edges of the game grid are glued together.
+    pub topology: Topology,
+    /// When a stagnating board is randomized again.
+    pub reseed_policy: ReseedPolicy,
+}
+
+/// A run of the Game of Life: the board, the age of its cells and the detection of still lifes and oscillators.
+///
+/// It holds the game logic of the firmware `refresh_gol_board` task, which only adds the timing and the logs, so the
+/// simulator replays the runs of the firmware from their seed. `HASH_HISTORY_LEN` is the longest detectable period.
+pub struct Game<const W: usize, const H: usize, const HASH_HISTORY_LEN: usize> {
+    board: GameGrid<W, H>,
+    ages: CellAges<W, H>,
+    detector: StagnationDetector<HASH_HISTORY_LEN>,
+    rng: XorShift32,
+}
+
+impl<const W: usize, const H: usize, const HASH_HISTORY_LEN: usize> Game<W, H, HASH_HISTORY_LEN> {
+    /// Creates a board randomized with `STARTUP_DENSITY`.
+    ///
+    /// # Arguments
+    ///
+    /// * `settings`: How the game runs.
+    /// * `seed`: The seed of the random number generator randomizing the board, runs with the same seed are identical.
+    pub fn new(settings: &GameSettings, seed: u32) -> Self {
+        let mut board = GameGrid::default();
+        board.set_topology(settings.topology);
+        let mut game = Game {
+            board,
+            ages: CellAges::new(),
+            detector: StagnationDetector::new(settings.reseed_policy),
+            rng: XorShift32::new(seed),
+        };
+        game.randomize(STARTUP_DENSITY);
+        game
+    }
+
+    /// Computes the next generation.
+    ///
+    /// # Arguments
+    ///
+    /// * `rule`: The rule of the automaton.
+    ///
+    /// # Returns
+    ///
+    /// Returns the changes of the board and whether it is stagnating, see `should_reseed`.
+    pub fn step(&mut self, rule: &Rule) -> (UpdateOutcome, Stagnation) {
+        let outcome = self.board.update_with(rule);
+        self.ages.observe(&self.board);
+        (outcome, self.detector.observe(self.board.get_hash()))
+    }
+
+    /// Returns `true` once the board stagnated for as long as the `ReseedPolicy` allows.
+    pub fn should_reseed(&self) -> bool {
+        self.detector.should_reseed()
+    }
+
+    /// Returns how long the board has been stagnating, in still-life generations or oscillator cycles.
+    pub fn repeats(&self) -> usize {
+        self.detector.repeats()
+    }
+
+    /// Randomizes the board again with `RESEED_DENSITY`, from the random number generator of the run.
+    pub fn reseed(&mut self) {
+        self.randomize(RESEED_DENSITY);
+    }
+
+    /// Returns the board.
+    pub fn board(&self) -> &GameGrid<W, H> {
+        &self.board
+    }
+
+    /// Returns the state and the age of each cell, as sent to the animation. `N` must be `W * H`.
+    pub fn generation<const N: usize>(&self) -> Generation<N> {
+        Generation {
+            alive: self.board.to_bool_arrray(),
+            ages: self.ages.to_array(),
+        }
+    }
+
+    fn randomize(&mut self, probability_to_live: f32) {
+        self.board
+            .randomize_with(&mut self.rng, probability_to_live);
+        self.detector.reset();
+        self.detector.observe(self.board.get_hash());
+        self.ages.reset();
+        self.ages.observe(&self.board);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SETTINGS: GameSettings = GameSettings {
+        topology: Topology::Bounded,
+        reseed_policy: ReseedPolicy {
+            still_life_repeats: 1,
+            oscillator_repeats: 3,
+        },
+    };
+
+    #[test]
+    fn runs_are_replayed_from_their_seed() {
+        let mut first = Game::<17, 8, 32>::new(&SETTINGS, 7);
+        let mut second = Game::<17, 8, 32>::new(&SETTINGS, 7);
+        (0..10).for_each(|_| {
+            assert_eq!(first.step(&Rule::default()), second.step(&Rule::default()));
+            assert_eq!(first.generation::<136>(), second.generation::<136>());
+        });
+        assert_ne!(
+            Game::<17, 8, 32>::new(&SETTINGS, 8).generation::<136>(),
+            second.generation::<136>()
+        );
+    }
+
+    #[test]
+    fn still_lifes_are_reseeded() {
+        let mut game = Game::<17, 8, 32>::new(&SETTINGS, 1);
+        // No cell is born nor survives, the board dies at once and stays empty.
+        let extinction: Rule = "B/S".parse().unwrap();
+        game.step(&extinction);
+        assert!(!game.should_reseed());
+        assert_eq!(game.step(&extinction).1, Stagnation::StillLife);
+        assert!(game.should_reseed());
+        assert!(!game.generation::<136>().alive.contains(&true));
+
+        game.reseed();
+        assert!(!game.should_reseed());
+        assert_eq!(game.repeats(), 0);
+        assert!(game.generation::<136>().alive.contains(&true));
+    }
+}
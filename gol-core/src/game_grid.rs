@@ -0,0 +1,628 @@
+use rand_core::RngCore;
+
+use crate::hash::hash_array;
+use crate::pattern::{Pattern, Transform};
+use crate::rule::Rule;
+use crate::topology::Topology;
+
+/// Summary of one generation step, returned by `GameGrid::update`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct UpdateOutcome {
+    /// Number of dead cells that became alive.
+    pub births: usize,
+    /// Number of alive cells that died.
+    pub deaths: usize,
+    /// Number of alive cells after the update.
+    pub population: usize,
+    /// `true` if at least one cell changed, i.e. `births + deaths > 0`.
+    pub changed: bool,
+}
+
+/// Represents the game grid with cells.
+///
+/// `W` is the width and `H` the height of the grid, in cells.
+#[derive(Clone)]
+pub struct GameGrid<const W: usize, const H: usize> {
+    /// Cells of the game grid. Stored as Row-major order.
+    cells: [[bool; W]; H],
+    /// How the edges of the grid are glued together.
+    topology: Topology,
+}
+
+impl<const W: usize, const H: usize> GameGrid<W, H> {
+    /// The number of cells of the game grid.
+    pub const CELLS: usize = W * H;
+
+    /// Updates the game grid with Conway's rule (`B3/S23`).
+    ///
+    /// # Returns
+    ///
+    /// What changed between the previous and the new generation.
+    pub fn update(&mut self) -> UpdateOutcome {
+        self.update_with(&Rule::CONWAY)
+    }
+
+    /// Updates the game grid with the given rule.
+    ///
+    /// # Arguments
+    ///
+    /// * `rule`: The birth/survival rule to apply.
+    ///
+    /// # Returns
+    ///
+    /// What changed between the previous and the new generation.
+    pub fn update_with(&mut self, rule: &Rule) -> UpdateOutcome {
+        let mut outcome = UpdateOutcome::default();
+        let mut new_cells = [[false; W]; H];
+        (0..H).for_each(|y| {
+            (0..W).for_each(|x| {
+                let neighbors = self.count_alive_neighbors(x, y);
+                let alive = rule.next_state(self.cells[y][x], neighbors);
+                match (self.cells[y][x], alive) {
+                    (false, true) => outcome.births += 1,
+                    (true, false) => outcome.deaths += 1,
+                    _ => {}
+                }
+                if alive {
+                    outcome.population += 1;
+                }
+                new_cells[y][x] = alive;
+            });
+        });
+
+        self.cells = new_cells;
+        outcome.changed = outcome.births + outcome.deaths > 0;
+        outcome
+    }
+
+    /// Randomizes the game grid using the provided random number generator.
+    ///
+    /// # Arguments
+    ///
+    /// * `rng`: The random number generator to draw the cells from.
+    /// * `probability_to_live`: The probability for each cell to be alive.
+    pub fn randomize_with<R: RngCore>(&mut self, rng: &mut R, probability_to_live: f32) {
+        let mut random: [u8; W] = [0; W];
+        let thresh = probability_to_live * u8::MAX as f32;
+        (0..H).for_each(|y| {
+            rng.fill_bytes(&mut random);
+            (0..W).for_each(|x| {
+                self.cells[y][x] = random[x] < thresh as u8;
+            });
+        });
+    }
+
+    /// Stamps a pattern on the game grid, leaving the cells around it untouched.
+    ///
+    /// # Arguments
+    ///
+    /// * `pattern`: The pattern to place.
+    /// * `x`, `y`: The position of the top left corner of the transformed pattern.
+    /// * `transform`: The rotation or mirroring applied to the pattern.
+    ///
+    /// # Returns
+    ///
+    /// Returns `true` if the whole pattern fits, otherwise the alive cells falling outside the grid are dropped.
+    pub fn stamp(&mut self, pattern: &Pattern, x: usize, y: usize, transform: Transform) -> bool {
+        let mut fits = true;
+        for (px, py) in pattern.cells() {
+            let (tx, ty) = transform.apply(px, py, pattern.width(), pattern.height());
+            match (x.checked_add(tx), y.checked_add(ty)) {
+                (Some(cx), Some(cy)) if cx < W && cy < H => self.cells[cy][cx] = true,
+                _ => fits = false,
+            }
+        }
+        fits
+    }
+
+    /// Computes the hash of the game grid.
+    ///
+    /// # Returns
+    ///
+    /// The hash of the game grid.
+    pub fn get_hash(&self) -> u64 {
+        hash_array(&self.cells)
+    }
+
+    /// Returns the state of the cell at `(x, y)` position.
+    pub fn is_alive(&self, x: usize, y: usize) -> bool {
+        self.cells[y][x]
+    }
+
+    /// Sets the state of the cell at `(x, y)` position.
+    pub fn set_alive(&mut self, x: usize, y: usize, alive: bool) {
+        self.cells[y][x] = alive;
+    }
+
+    /// Returns the row at `y` position.
+    pub fn row(&self, y: usize) -> &[bool; W] {
+        &self.cells[y]
+    }
+
+    /// Iterates over the cells of the game grid, in row-major order.
+    pub fn cells(&self) -> impl Iterator<Item = bool> + '_ {
+        self.cells.iter().flatten().copied()
+    }
+
+    /// Converts the game grid to a boolean array.
+    ///
+    /// The length `N` of the array must be equal to `W * H`, this is checked at compile time.
+    ///
+    /// # Returns
+    ///
+    /// A boolean array representing the game grid.
+    pub fn to_bool_arrray<const N: usize>(&self) -> [bool; N] {
+        #[allow(clippy::let_unit_value)]
+        let () = AssertCells::<W, H, N>::OK;
+        let mut array: [bool; N] = [false; N];
+        (0..H).for_each(|y| {
+            (0..W).for_each(|x| {
+                array[y * W + x] = self.cells[y][x];
+            });
+        });
+        array
+    }
+
+    /// Returns the topology of the game grid.
+    pub fn topology(&self) -> Topology {
+        self.topology
+    }
+
+    /// Sets the topology of the game grid, used by the next updates.
+    ///
+    /// # Arguments
+    ///
+    /// * `topology`: How the edges of the grid are glued together.
+    pub fn set_topology(&mut self, topology: Topology) {
+        self.topology = topology;
+    }
+
+    /// Computes the number of alive neighbors of the cell at `(x, y)` position.
+    pub fn count_alive_neighbors(&self, x: usize, y: usize) -> u8 {
+        let mut count = 0;
+        for dy in [-1, 0, 1] {
+            for dx in [-1, 0, 1] {
+                if dx == 0 && dy == 0 {
+                    continue;
+                }
+                if let Some((nx, ny)) = self.topology.neighbor(x, y, dx, dy, W, H) {
+                    if self.cells[ny][nx] {
+                        count += 1;
+                    }
+                }
+            }
+        }
+        count
+    }
+}
+
+impl<const W: usize, const H: usize> Default for GameGrid<W, H> {
+    /// Creates a new instance of `GameGrid` with default values (false).
+    ///
+    /// # Returns
+    ///
+    /// A new instance of `GameGrid` with all cells set to `false` and bounded edges.
+    fn default() -> Self {
+        GameGrid {
+            cells: [[false; W]; H],
+            topology: Topology::default(),
+        }
+    }
+}
+
+/// Compile-time check that an array of `N` elements can hold a `W` x `H` game grid.
+pub(crate) struct AssertCells<const W: usize, const H: usize, const N: usize>;
+
+impl<const W: usize, const H: usize, const N: usize> AssertCells<W, H, N> {
+    pub(crate) const OK: () = assert!(
+        W * H == N,
+        "array length must be equal to the game grid size"
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WIDTH: usize = 17;
+    const HEIGHT: usize = 8;
+
+    type Grid = GameGrid<WIDTH, HEIGHT>;
+
+    fn grid_with(cells: &[(usize, usize)]) -> Grid {
+        let mut gg = Grid::default();
+        for &(x, y) in cells {
+            gg.set_alive(x, y, true);
+        }
+        gg
+    }
+
+    fn alive_cells<const W: usize, const H: usize>(gg: &GameGrid<W, H>) -> usize {
+        gg.cells().filter(|c| *c).count()
+    }
+
+    #[test]
+    fn dead_cell_with_three_neighbors_is_born() {
+        let mut gg = grid_with(&[(4, 3), (5, 3), (6, 3)]);
+        gg.update();
+        assert!(gg.is_alive(5, 2));
+        assert!(gg.is_alive(5, 4));
+    }
+
+    #[test]
+    fn live_cell_survives_with_two_or_three_neighbors() {
+        // Block: every cell has exactly three neighbors.
+        let mut gg = grid_with(&[(2, 2), (3, 2), (2, 3), (3, 3)]);
+        gg.update();
+        assert!(gg.is_alive(2, 2) && gg.is_alive(3, 2) && gg.is_alive(2, 3) && gg.is_alive(3, 3));
+        assert_eq!(alive_cells(&gg), 4);
+
+        // Center of a blinker has two neighbors.
+        let mut gg = grid_with(&[(4, 3), (5, 3), (6, 3)]);
+        gg.update();
+        assert!(gg.is_alive(5, 3));
+    }
+
+    #[test]
+    fn live_cell_dies_of_under_and_overpopulation() {
+        let mut gg = grid_with(&[(5, 5), (6, 5)]);
+        gg.update();
+        assert_eq!(alive_cells(&gg), 0);
+
+        // Center of a plus sign has four neighbors.
+        let mut gg = grid_with(&[(5, 3), (4, 4), (5, 4), (6, 4), (5, 5)]);
+        gg.update();
+        assert!(!gg.is_alive(5, 4));
+    }
+
+    #[test]
+    fn blinker_oscillates_with_period_two() {
+        let mut gg = grid_with(&[(4, 3), (5, 3), (6, 3)]);
+        let start = gg.get_hash();
+        gg.update();
+        assert_ne!(gg.get_hash(), start);
+        gg.update();
+        assert_eq!(gg.get_hash(), start);
+    }
+
+    #[test]
+    fn cells_outside_the_grid_are_dead() {
+        let gg = grid_with(&[(0, 0), (1, 0), (0, 1)]);
+        assert_eq!(gg.count_alive_neighbors(0, 0), 2);
+        assert_eq!(gg.count_alive_neighbors(WIDTH - 1, HEIGHT - 1), 0);
+
+        // A blinker lying on the top edge cannot grow outside the grid.
+        let mut gg = grid_with(&[(0, 0), (0, 1), (0, 2)]);
+        gg.update();
+        assert!(gg.is_alive(0, 1) && gg.is_alive(1, 1));
+        assert_eq!(alive_cells(&gg), 2);
+    }
+
+    #[test]
+    fn corner_neighbors_are_counted_without_wrapping() {
+        let gg = grid_with(&[(WIDTH - 1, 0), (0, HEIGHT - 1)]);
+        assert_eq!(gg.count_alive_neighbors(0, 0), 0);
+        assert_eq!(gg.count_alive_neighbors(WIDTH - 2, 1), 1);
+    }
+
+    #[test]
+    fn randomize_with_honors_extreme_probabilities() {
+        struct Constant(u8);
+        impl RngCore for Constant {
+            fn next_u32(&mut self) -> u32 {
+                u32::from_le_bytes([self.0; 4])
+            }
+            fn next_u64(&mut self) -> u64 {
+                u64::from_le_bytes([self.0; 8])
+            }
+            fn fill_bytes(&mut self, dest: &mut [u8]) {
+                dest.fill(self.0);
+            }
+            fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand_core::Error> {
+                self.fill_bytes(dest);
+                Ok(())
+            }
+        }
+
+        let mut gg = Grid::default();
+        gg.randomize_with(&mut Constant(0), 1.0);
+        assert_eq!(alive_cells(&gg), WIDTH * HEIGHT);
+        gg.randomize_with(&mut Constant(0), 0.0);
+        assert_eq!(alive_cells(&gg), 0);
+    }
+
+    #[test]
+    fn grid_dimensions_are_generic() {
+        assert_eq!(GameGrid::<16, 16>::CELLS, 256);
+        assert_eq!(GameGrid::<32, 8>::CELLS, 256);
+        assert_eq!(GameGrid::<16, 9>::CELLS, 144);
+
+        // Blinker on the far edge of a wide, flat panel.
+        let mut gg = GameGrid::<32, 8>::default();
+        gg.set_alive(29, 4, true);
+        gg.set_alive(30, 4, true);
+        gg.set_alive(31, 4, true);
+        gg.update();
+        assert!(gg.is_alive(30, 3) && gg.is_alive(30, 4) && gg.is_alive(30, 5));
+        assert_eq!(alive_cells(&gg), 3);
+    }
+
+    #[test]
+    fn bool_array_is_row_major() {
+        let gg = grid_with(&[(1, 0), (0, 1)]);
+        let array: [bool; WIDTH * HEIGHT] = gg.to_bool_arrray();
+        assert!(array[1]);
+        assert!(array[WIDTH]);
+        assert_eq!(array.iter().filter(|c| **c).count(), 2);
+        assert!(array.iter().copied().eq(gg.cells()));
+    }
+
+    #[test]
+    fn update_with_applies_the_given_rule() {
+        // Under Seeds, a domino gives birth to the cells above and below it and dies.
+        let mut gg = grid_with(&[(4, 3), (5, 3)]);
+        gg.update_with(&Rule::SEEDS);
+        assert!(!gg.is_alive(4, 3) && !gg.is_alive(5, 3));
+        assert!(gg.is_alive(4, 2) && gg.is_alive(5, 2) && gg.is_alive(4, 4) && gg.is_alive(5, 4));
+        assert_eq!(alive_cells(&gg), 4);
+
+        // Under HighLife, a dead cell with six neighbors is born.
+        let mut gg = grid_with(&[(4, 2), (5, 2), (6, 2), (4, 4), (5, 4), (6, 4)]);
+        gg.update_with(&Rule::HIGHLIFE);
+        assert!(gg.is_alive(5, 3));
+        let mut gg = grid_with(&[(4, 2), (5, 2), (6, 2), (4, 4), (5, 4), (6, 4)]);
+        gg.update();
+        assert!(!gg.is_alive(5, 3));
+    }
+
+    /// A glider moving toward the bottom right corner, its top left corner at `(x, y)`.
+    fn glider_at(topology: Topology, x: usize, y: usize) -> Grid {
+        let mut gg = grid_with(&[]);
+        gg.set_topology(topology);
+        for (dx, dy) in [(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)] {
+            gg.set_alive((x + dx) % WIDTH, (y + dy) % HEIGHT, true);
+        }
+        gg
+    }
+
+    /// Checks `generations` updates of `start` against the same board unfolded on a larger bounded plane.
+    ///
+    /// The grid is placed at `origin` on the plane and `fold` maps any plane position, relative to `origin`,
+    /// back to the grid cell it is a copy of. Both must stay equal as long as the plane border is far enough.
+    fn assert_matches_unfolded<const PW: usize, const PH: usize>(
+        start: &Grid,
+        generations: usize,
+        origin: (usize, usize),
+        fold: impl Fn(isize, isize) -> (usize, usize),
+    ) {
+        let mut gg = grid_with(&[]);
+        gg.set_topology(start.topology());
+        let mut plane = GameGrid::<PW, PH>::default();
+        for py in 0..PH {
+            for px in 0..PW {
+                let (x, y) = fold(
+                    px as isize - origin.0 as isize,
+                    py as isize - origin.1 as isize,
+                );
+                plane.set_alive(px, py, start.is_alive(x, y));
+                gg.set_alive(x, y, start.is_alive(x, y));
+            }
+        }
+
+        for generation in 1..=generations {
+            gg.update();
+            plane.update();
+            for y in 0..HEIGHT {
+                for x in 0..WIDTH {
+                    assert_eq!(
+                        gg.is_alive(x, y),
+                        plane.is_alive(origin.0 + x, origin.1 + y),
+                        "cell ({}, {}) differs at generation {}",
+                        x,
+                        y,
+                        generation
+                    );
+                }
+            }
+        }
+    }
+
+    /// Maps `v` to `0..len`, mirroring every other copy when `mirrored` is set.
+    fn fold_axis(v: isize, len: usize, mirrored: bool) -> usize {
+        let len = len as isize;
+        let (copy, r) = (v.div_euclid(len), v.rem_euclid(len));
+        if mirrored && copy % 2 != 0 {
+            (len - 1 - r) as usize
+        } else {
+            r as usize
+        }
+    }
+
+    #[test]
+    fn bounded_glider_stops_in_the_corner() {
+        let mut gg = glider_at(Topology::Bounded, WIDTH - 6, HEIGHT - 6);
+        for _ in 0..40 {
+            gg.update();
+        }
+        // The glider crashes into the corner and settles as a block.
+        assert_eq!(alive_cells(&gg), 4);
+        let block = [
+            (WIDTH - 2, HEIGHT - 2),
+            (WIDTH - 1, HEIGHT - 2),
+            (WIDTH - 2, HEIGHT - 1),
+        ];
+        assert!(block.iter().all(|&(x, y)| gg.is_alive(x, y)));
+        assert!(gg.is_alive(WIDTH - 1, HEIGHT - 1));
+    }
+
+    #[test]
+    fn torus_glider_crosses_the_corner() {
+        let start = glider_at(Topology::Torus, WIDTH - 4, HEIGHT - 4);
+        assert_matches_unfolded::<{ WIDTH * 5 }, { HEIGHT * 7 }>(
+            &start,
+            20,
+            (WIDTH * 2, HEIGHT * 3),
+            |x, y| (fold_axis(x, WIDTH, false), fold_axis(y, HEIGHT, false)),
+        );
+    }
+
+    #[test]
+    fn torus_glider_comes_back_to_its_start() {
+        let mut gg = glider_at(Topology::Torus, 2, 2);
+        let start = gg.get_hash();
+        // A glider moves one cell diagonally every 4 generations: it needs lcm(17, 8) cells to loop.
+        for _ in 0..4 * WIDTH * HEIGHT {
+            gg.update();
+            assert_eq!(alive_cells(&gg), 5);
+        }
+        assert_eq!(gg.get_hash(), start);
+    }
+
+    #[test]
+    fn klein_bottle_glider_comes_back_mirrored() {
+        let start = glider_at(Topology::KleinBottle, WIDTH - 8, HEIGHT - 4);
+        assert_matches_unfolded::<{ WIDTH * 5 }, { HEIGHT * 7 }>(
+            &start,
+            20,
+            (WIDTH * 2, HEIGHT * 3),
+            |x, y| {
+                let mirrored = y.div_euclid(HEIGHT as isize) % 2 != 0;
+                let x = if mirrored { WIDTH as isize - 1 - x } else { x };
+                (fold_axis(x, WIDTH, false), fold_axis(y, HEIGHT, false))
+            },
+        );
+
+        // Once through the bottom edge, the glider travels toward the left.
+        let mut gg = start;
+        for _ in 0..24 {
+            gg.update();
+        }
+        let mut expected = grid_with(&[]);
+        for y in 0..HEIGHT {
+            for x in 0..WIDTH {
+                expected.set_alive((x + WIDTH - 1) % WIDTH, (y + 1) % HEIGHT, gg.is_alive(x, y));
+            }
+        }
+        for _ in 0..4 {
+            gg.update();
+        }
+        assert_eq!(gg.get_hash(), expected.get_hash());
+    }
+
+    #[test]
+    fn cylinder_glider_crosses_the_side() {
+        let start = glider_at(Topology::Cylinder, WIDTH - 3, 0);
+        assert_matches_unfolded::<{ WIDTH * 5 }, HEIGHT>(&start, 16, (WIDTH * 2, 0), |x, y| {
+            (fold_axis(x, WIDTH, false), y as usize)
+        });
+    }
+
+    #[test]
+    fn cylinder_glider_stops_at_the_bottom() {
+        let mut gg = glider_at(Topology::Cylinder, WIDTH - 3, 0);
+        for _ in 0..40 {
+            gg.update();
+        }
+        assert_eq!(alive_cells(&gg), 4);
+    }
+
+    #[test]
+    fn mirror_glider_bounces_on_the_edges() {
+        let start = glider_at(Topology::Mirror, WIDTH - 5, HEIGHT - 5);
+        assert_matches_unfolded::<{ WIDTH * 5 }, { HEIGHT * 7 }>(
+            &start,
+            20,
+            (WIDTH * 2, HEIGHT * 3),
+            |x, y| (fold_axis(x, WIDTH, true), fold_axis(y, HEIGHT, true)),
+        );
+    }
+
+    #[test]
+    fn update_reports_births_deaths_and_population() {
+        // Horizontal blinker turns vertical: two cells die, two are born.
+        let mut gg = grid_with(&[(4, 3), (5, 3), (6, 3)]);
+        let outcome = gg.update();
+        assert_eq!(
+            outcome,
+            UpdateOutcome {
+                births: 2,
+                deaths: 2,
+                population: 3,
+                changed: true,
+            }
+        );
+    }
+
+    #[test]
+    fn update_reports_no_change_for_still_life() {
+        let mut gg = grid_with(&[(2, 2), (3, 2), (2, 3), (3, 3)]);
+        let outcome = gg.update();
+        assert!(!outcome.changed);
+        assert_eq!((outcome.births, outcome.deaths), (0, 0));
+        assert_eq!(outcome.population, 4);
+
+        let mut gg = grid_with(&[]);
+        assert_eq!(gg.update(), UpdateOutcome::default());
+    }
+
+    #[test]
+    fn update_reports_change_when_everything_dies() {
+        let mut gg = grid_with(&[(5, 5)]);
+        let outcome = gg.update();
+        assert!(outcome.changed);
+        assert_eq!((outcome.births, outcome.deaths), (0, 1));
+        assert_eq!(outcome.population, 0);
+    }
+
+    #[test]
+    fn stamped_glider_flies_in_its_transformed_direction() {
+        use crate::pattern::classics::GLIDER;
+
+        // Identity moves toward the bottom right, FlipHorizontal toward the bottom left.
+        for (transform, dx) in [(Transform::Identity, 1), (Transform::FlipHorizontal, -1)] {
+            let mut gg = grid_with(&[]);
+            assert!(gg.stamp(&GLIDER, 7, 1, transform));
+            let start: [bool; WIDTH * HEIGHT] = gg.to_bool_arrray();
+            for _ in 0..4 {
+                gg.update();
+            }
+            for y in 0..HEIGHT - 1 {
+                for x in 1..WIDTH - 1 {
+                    let moved = (x as isize + dx) as usize;
+                    assert_eq!(gg.is_alive(moved, y + 1), start[y * WIDTH + x]);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn stamp_keeps_existing_cells_and_clips_outside_ones() {
+        use crate::pattern::classics::{ACORN, PULSAR};
+
+        let mut gg = grid_with(&[(0, 0)]);
+        assert!(gg.stamp(&ACORN, 10, 5, Transform::Identity));
+        assert!(gg.is_alive(0, 0));
+        assert_eq!(alive_cells(&gg), 8);
+
+        let mut gg = grid_with(&[]);
+        assert!(!gg.stamp(&ACORN, 10, 5, Transform::Rotate90));
+        assert!(!gg.stamp(&PULSAR, 2, 0, Transform::Identity));
+        assert!(!gg.stamp(&ACORN, usize::MAX, 0, Transform::Identity));
+    }
+
+    #[test]
+    fn stamped_pulsar_has_period_three() {
+        use crate::pattern::classics::PULSAR;
+
+        let mut gg = GameGrid::<17, 17>::default();
+        assert!(gg.stamp(&PULSAR, 2, 2, Transform::Identity));
+        let start = gg.get_hash();
+        gg.update();
+        assert_ne!(gg.get_hash(), start);
+        gg.update();
+        assert_ne!(gg.get_hash(), start);
+        gg.update();
+        assert_eq!(gg.get_hash(), start);
+    }
+}
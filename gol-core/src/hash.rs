@@ -0,0 +1,78 @@
+use core::hash::{Hash, Hasher};
+
+/// Computes the hash of an array.
+///
+/// # Arguments
+///
+/// * `arr`: The array to compute the hash for.
+///
+/// # Returns
+///
+/// The hash of the array.
+pub fn hash_array<T: Hash>(arr: &[T]) -> u64 {
+    let mut hasher = ArrayHasher::new();
+    arr.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// A minimal multiplicative hasher (`state * 31 + byte`), usable without `std`.
+pub struct ArrayHasher {
+    state: u64,
+}
+
+impl ArrayHasher {
+    /// Creates a new instance of `ArrayHasher`.
+    ///
+    /// # Returns
+    ///
+    /// A new instance of `ArrayHasher`.
+    pub fn new() -> Self {
+        ArrayHasher { state: 0 }
+    }
+}
+
+impl Default for ArrayHasher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Hasher for ArrayHasher {
+    fn finish(&self) -> u64 {
+        self.state
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes.iter() {
+            self.state = self.state.wrapping_mul(31).wrapping_add(*byte as u64);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hasher_folds_bytes_in_order() {
+        let mut hasher = ArrayHasher::new();
+        hasher.write(&[1, 2, 3]);
+        assert_eq!(hasher.finish(), (31 + 2) * 31 + 3);
+    }
+
+    #[test]
+    fn equal_arrays_have_equal_hashes() {
+        let a = [[true, false], [false, true]];
+        let b = [[true, false], [false, true]];
+        assert_eq!(hash_array(&a), hash_array(&b));
+    }
+
+    #[test]
+    fn different_arrays_have_different_hashes() {
+        let a = [[true, false], [false, true]];
+        let b = [[false, true], [true, false]];
+        let c = [[false, false], [false, false]];
+        assert_ne!(hash_array(&a), hash_array(&b));
+        assert_ne!(hash_array(&a), hash_array(&c));
+    }
+}
@@ -0,0 +1,288 @@
+use cichlid::ColorRGB;
+
+use crate::palette::mix;
+
+/// An animation of the cells that keep their state between two generations, so a steady board does not look frozen.
+///
+/// Periods are counted in animation frames. Every LED runs the animation with its own phase, see `led_phase`,
+/// so the board does not pulse in lockstep.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum IdleEffect {
+    /// The color is kept as is.
+    None,
+    /// The brightness slowly rises and falls along a sine.
+    Breathing {
+        /// The number of frames of one breath.
+        period: u16,
+        /// How much the brightness falls at the bottom of a breath, `255` going down to black.
+        depth: u8,
+    },
+    /// LEDs randomly flash brighter for a short while.
+    Twinkle {
+        /// The chance out of `256` for a LED to twinkle during each `length` frames window.
+        chance: u8,
+        /// The number of frames of a twinkle.
+        length: u16,
+    },
+    /// The hue slowly goes around the color wheel, by rotating the red, green and blue channels.
+    HueDrift {
+        /// The number of frames of a full turn.
+        period: u16,
+    },
+    /// A faint glow is added along a sine, meant for dead cells.
+    Shimmer {
+        /// The color added at the top of the sine.
+        color: ColorRGB,
+        /// The number of frames of one glow.
+        period: u16,
+    },
+}
+
+impl IdleEffect {
+    /// Animates the color of a steady LED.
+    ///
+    /// # Arguments
+    ///
+    /// * `color`: The color of the LED without animation.
+    /// * `led`: The index of the LED, which sets its phase.
+    /// * `tick`: The number of frames since the animation started.
+    pub fn apply(&self, color: ColorRGB, led: usize, tick: u32) -> ColorRGB {
+        let phase = led_phase(led);
+        match *self {
+            IdleEffect::None => color,
+            IdleEffect::Breathing { period, depth } => {
+                let wave = sin8(angle(tick, period).wrapping_add(phase)) as u16;
+                let factor = 255 - depth as u16 * (255 - wave) / 255;
+                map_channels(color, |c| (c as u16 * factor / 255) as u8)
+            }
+            IdleEffect::Twinkle { chance, length } => {
+                let length = length.max(1) as u32;
+                // Staggers the windows of the LEDs.
+                let tick = tick.wrapping_add(phase as u32 * length / 256);
+                let window = tick / length;
+                if noise(led as u32, window) as u8 >= chance {
+                    return color;
+                }
+                // Triangle envelope over the window, doubling the brightness at its peak.
+                let position = tick % length;
+                let envelope = 255 - (2 * position * 255 / length).abs_diff(255).min(255);
+                map_channels(color, |c| {
+                    c.saturating_add((c as u32 * envelope / 255) as u8)
+                })
+            }
+            IdleEffect::HueDrift { period } => {
+                let turn = (tick % period.max(1) as u32) * 768 / period.max(1) as u32;
+                let turn = (turn + phase as u32 * 3) % 768;
+                let (sector, amount) = (turn / 256, (turn % 256) as u8);
+                mix(rotate(color, sector), rotate(color, sector + 1), amount)
+            }
+            IdleEffect::Shimmer {
+                color: glow,
+                period,
+            } => {
+                let wave = sin8(angle(tick, period).wrapping_add(phase)) as u16;
+                ColorRGB {
+                    r: color.r.saturating_add((glow.r as u16 * wave / 255) as u8),
+                    g: color.g.saturating_add((glow.g as u16 * wave / 255) as u8),
+                    b: color.b.saturating_add((glow.b as u16 * wave / 255) as u8),
+                }
+            }
+        }
+    }
+}
+
+/// The idle effects of the alive and of the dead cells.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct IdleAnimation {
+    /// The effect of the cells that stay alive.
+    pub alive: IdleEffect,
+    /// The effect of the cells that stay dead.
+    pub dead: IdleEffect,
+}
+
+impl IdleAnimation {
+    /// Steady cells keep a constant color.
+    pub const NONE: IdleAnimation = IdleAnimation {
+        alive: IdleEffect::None,
+        dead: IdleEffect::None,
+    };
+
+    /// Animates the color of a LED whose cell kept its state, see `IdleEffect::apply`.
+    pub fn apply(&self, color: ColorRGB, alive: bool, led: usize, tick: u32) -> ColorRGB {
+        if alive {
+            self.alive.apply(color, led, tick)
+        } else {
+            self.dead.apply(color, led, tick)
+        }
+    }
+
+    /// Returns `true` if any effect changes the colors over time, i.e. frames are worth rendering between generations.
+    pub fn is_animated(&self) -> bool {
+        *self != Self::NONE
+    }
+}
+
+impl Default for IdleAnimation {
+    fn default() -> Self {
+        Self::NONE
+    }
+}
+
+/// Returns the phase of the idle animation of a LED, spread over the whole cycle by Fibonacci hashing.
+pub fn led_phase(led: usize) -> u8 {
+    ((led as u32).wrapping_mul(0x9E37_79B9) >> 24) as u8
+}
+
+/// Approximates a sine over a full turn of `256`, centered on `128`, with two parabolas.
+pub fn sin8(angle: u8) -> u8 {
+    let half = (angle & 0x7F) as u32;
+    let bump = (half * (128 - half) * 127 / 4096) as u8;
+    if angle < 128 {
+        128 + bump
+    } else {
+        128 - bump
+    }
+}
+
+/// Returns the position of `tick` in a cycle of `period` frames, as an angle of a full turn of `256`.
+fn angle(tick: u32, period: u16) -> u8 {
+    let period = period.max(1) as u32;
+    ((tick % period) * 256 / period) as u8
+}
+
+/// Hashes two numbers to a pseudo-random one, deterministic so animations can be tested and replayed.
+fn noise(a: u32, b: u32) -> u32 {
+    let mut x = a.wrapping_mul(0x9E37_79B9) ^ b.wrapping_mul(0x85EB_CA6B);
+    x ^= x >> 16;
+    x = x.wrapping_mul(0x7FEB_352D);
+    x ^= x >> 15;
+    x = x.wrapping_mul(0x846C_A68B);
+    x ^ (x >> 16)
+}
+
+/// Rotates the channels of a color `times` times: red to green, green to blue and blue to red.
+fn rotate(color: ColorRGB, times: u32) -> ColorRGB {
+    match times % 3 {
+        0 => color,
+        1 => ColorRGB {
+            r: color.b,
+            g: color.r,
+            b: color.g,
+        },
+        _ => ColorRGB {
+            r: color.g,
+            g: color.b,
+            b: color.r,
+        },
+    }
+}
+
+fn map_channels(color: ColorRGB, f: impl Fn(u8) -> u8) -> ColorRGB {
+    ColorRGB {
+        r: f(color.r),
+        g: f(color.g),
+        b: f(color.b),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const COLOR: ColorRGB = ColorRGB {
+        r: 200,
+        g: 100,
+        b: 20,
+    };
+
+    #[test]
+    fn sin8_covers_the_whole_range() {
+        assert_eq!(sin8(0), 128);
+        assert_eq!(sin8(64), 255);
+        assert_eq!(sin8(128), 128);
+        assert_eq!(sin8(192), 1);
+        assert!((0..64).all(|a| sin8(a) <= sin8(a + 1)));
+    }
+
+    #[test]
+    fn none_keeps_the_color() {
+        for tick in 0..100 {
+            assert_eq!(IdleAnimation::NONE.apply(COLOR, true, 3, tick), COLOR);
+        }
+        assert!(!IdleAnimation::NONE.is_animated());
+    }
+
+    #[test]
+    fn breathing_stays_within_its_depth() {
+        let effect = IdleEffect::Breathing {
+            period: 100,
+            depth: 128,
+        };
+        let reds: Vec<u8> = (0..100)
+            .map(|tick| effect.apply(COLOR, 0, tick).r)
+            .collect();
+        let (min, max) = (*reds.iter().min().unwrap(), *reds.iter().max().unwrap());
+        assert!((COLOR.r - 2..=COLOR.r).contains(&max));
+        assert!((COLOR.r / 2 - 1..=COLOR.r / 2 + 2).contains(&min));
+        assert_eq!(effect.apply(COLOR, 0, 7), effect.apply(COLOR, 0, 107));
+    }
+
+    #[test]
+    fn leds_are_out_of_phase() {
+        let effect = IdleEffect::Breathing {
+            period: 100,
+            depth: 255,
+        };
+        let phases: Vec<u8> = (0..16).map(led_phase).collect();
+        let mut distinct = phases.clone();
+        distinct.sort();
+        distinct.dedup();
+        assert_eq!(distinct.len(), phases.len());
+        assert!((1..16).any(|led| effect.apply(COLOR, led, 0) != effect.apply(COLOR, 0, 0)));
+    }
+
+    #[test]
+    fn twinkle_follows_its_chance() {
+        let twinkling = |chance| {
+            let effect = IdleEffect::Twinkle { chance, length: 10 };
+            (0..100)
+                .filter(|&led| (0..10).any(|tick| effect.apply(COLOR, led, tick) != COLOR))
+                .count()
+        };
+        assert_eq!(twinkling(0), 0);
+        assert!(twinkling(255) > 90);
+        let some = twinkling(64);
+        assert!(some > 5 && some < 60, "{}", some);
+
+        let effect = IdleEffect::Twinkle {
+            chance: 255,
+            length: 10,
+        };
+        for tick in 0..10 {
+            let color = effect.apply(COLOR, 0, tick);
+            assert!(color.g >= COLOR.g && color.g <= COLOR.g * 2);
+        }
+    }
+
+    #[test]
+    fn hue_drift_goes_around_the_wheel() {
+        let effect = IdleEffect::HueDrift { period: 300 };
+        let colors: Vec<ColorRGB> = (0..300).map(|tick| effect.apply(COLOR, 0, tick)).collect();
+        assert!(colors.contains(&rotate(COLOR, 1)) || colors.contains(&rotate(COLOR, 2)));
+        assert_eq!(effect.apply(COLOR, 0, 42), effect.apply(COLOR, 0, 342));
+    }
+
+    #[test]
+    fn shimmer_adds_a_faint_glow() {
+        let glow = ColorRGB { r: 0, g: 0, b: 4 };
+        let effect = IdleEffect::Shimmer {
+            color: glow,
+            period: 50,
+        };
+        let black = ColorRGB::default();
+        let blues: Vec<u8> = (0..50).map(|tick| effect.apply(black, 5, tick).b).collect();
+        assert!((3..=4).contains(blues.iter().max().unwrap()));
+        assert!(blues.iter().any(|&b| b < 2));
+        assert!((0..50).all(|tick| effect.apply(black, 5, tick).r == 0));
+    }
+}
@@ -0,0 +1,429 @@
+use crate::pattern::Transform;
+
+/// How the cells of a grid are wired on a physical LED strip.
+///
+/// Grid coordinates start at the top left corner of the board as seen by the viewer, LED indices at the data
+/// input of the strip.
+pub trait LedLayout {
+    /// Returns the width of the grid, in cells.
+    fn width(&self) -> usize;
+
+    /// Returns the height of the grid, in cells.
+    fn height(&self) -> usize;
+
+    /// Returns the index on the strip of the LED showing the cell at `(x, y)`, or `None` if no LED shows it.
+    fn led_index(&self, x: usize, y: usize) -> Option<usize>;
+
+    /// Reorders values from the row-major order of the grid to the order of the strip.
+    ///
+    /// # Arguments
+    ///
+    /// * `cells`: One value per cell, row by row from the top left corner, e.g. `GameGrid::to_bool_arrray`.
+    /// * `leds`: One value per LED. LEDs showing no cell, or beyond the end of `leds`, are left untouched.
+    fn arrange<T: Copy>(&self, cells: &[T], leds: &mut [T])
+    where
+        Self: Sized,
+    {
+        (0..self.height()).for_each(|y| {
+            (0..self.width()).for_each(|x| {
+                let led = self.led_index(x, y).and_then(|i| leds.get_mut(i));
+                if let (Some(led), Some(&cell)) = (led, cells.get(y * self.width() + x)) {
+                    *led = cell;
+                }
+            });
+        });
+    }
+}
+
+/// Every row is wired left to right, the strip going back to the left edge between rows.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RowMajor {
+    width: usize,
+    height: usize,
+}
+
+impl RowMajor {
+    /// Creates the layout of a `width` x `height` grid.
+    pub const fn new(width: usize, height: usize) -> Self {
+        RowMajor { width, height }
+    }
+}
+
+impl LedLayout for RowMajor {
+    fn width(&self) -> usize {
+        self.width
+    }
+
+    fn height(&self) -> usize {
+        self.height
+    }
+
+    fn led_index(&self, x: usize, y: usize) -> Option<usize> {
+        (x < self.width && y < self.height).then_some(y * self.width + x)
+    }
+}
+
+/// The strip zigzags along the rows: even rows are wired left to right, odd rows right to left.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Serpentine {
+    width: usize,
+    height: usize,
+}
+
+impl Serpentine {
+    /// Creates the layout of a `width` x `height` grid.
+    pub const fn new(width: usize, height: usize) -> Self {
+        Serpentine { width, height }
+    }
+}
+
+impl LedLayout for Serpentine {
+    fn width(&self) -> usize {
+        self.width
+    }
+
+    fn height(&self) -> usize {
+        self.height
+    }
+
+    fn led_index(&self, x: usize, y: usize) -> Option<usize> {
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+        let column = if y % 2 == 1 { self.width - 1 - x } else { x };
+        Some(y * self.width + column)
+    }
+}
+
+/// The strip zigzags along the columns: even columns are wired top to bottom, odd columns bottom to top.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ColumnSerpentine {
+    width: usize,
+    height: usize,
+}
+
+impl ColumnSerpentine {
+    /// Creates the layout of a `width` x `height` grid.
+    pub const fn new(width: usize, height: usize) -> Self {
+        ColumnSerpentine { width, height }
+    }
+}
+
+impl LedLayout for ColumnSerpentine {
+    fn width(&self) -> usize {
+        self.width
+    }
+
+    fn height(&self) -> usize {
+        self.height
+    }
+
+    fn led_index(&self, x: usize, y: usize) -> Option<usize> {
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+        let row = if x % 2 == 1 { self.height - 1 - y } else { y };
+        Some(x * self.height + row)
+    }
+}
+
+/// A layout mounted rotated or flipped, e.g. a serpentine strip whose data input is at the bottom right corner.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Transformed<L> {
+    layout: L,
+    transform: Transform,
+}
+
+impl<L: LedLayout> Transformed<L> {
+    /// Creates a layout showing the grid transformed on the physical `layout`.
+    ///
+    /// # Arguments
+    ///
+    /// * `layout`: The wiring of the strip, as seen by the viewer.
+    /// * `transform`: Where a grid cell lands on `layout`, e.g. `Transform::Rotate90` shows the grid rotated a
+    ///   quarter turn clockwise. Quarter turns and diagonal flips swap the width and the height of the grid.
+    pub const fn new(layout: L, transform: Transform) -> Self {
+        Transformed { layout, transform }
+    }
+}
+
+impl<L: LedLayout> LedLayout for Transformed<L> {
+    fn width(&self) -> usize {
+        self.transform
+            .dimensions(self.layout.width(), self.layout.height())
+            .0
+    }
+
+    fn height(&self) -> usize {
+        self.transform
+            .dimensions(self.layout.width(), self.layout.height())
+            .1
+    }
+
+    fn led_index(&self, x: usize, y: usize) -> Option<usize> {
+        if x >= self.width() || y >= self.height() {
+            return None;
+        }
+        let (x, y) = self.transform.apply(x, y, self.width(), self.height());
+        self.layout.led_index(x, y)
+    }
+}
+
+/// An explicit cell to LED table, for irregular installs.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LookupTable<'a> {
+    width: usize,
+    height: usize,
+    leds: &'a [Option<usize>],
+}
+
+impl<'a> LookupTable<'a> {
+    /// Creates the layout of a `width` x `height` grid from a table.
+    ///
+    /// # Arguments
+    ///
+    /// * `leds`: The LED index of each cell, row by row from the top left corner, `None` for cells without LED.
+    ///
+    /// # Panics
+    ///
+    /// If `leds` does not hold exactly one entry per cell, which fails the build in a `const`.
+    pub const fn new(width: usize, height: usize, leds: &'a [Option<usize>]) -> Self {
+        assert!(
+            leds.len() == width * height,
+            "the lookup table must hold one entry per cell"
+        );
+        LookupTable {
+            width,
+            height,
+            leds,
+        }
+    }
+}
+
+impl LedLayout for LookupTable<'_> {
+    fn width(&self) -> usize {
+        self.width
+    }
+
+    fn height(&self) -> usize {
+        self.height
+    }
+
+    fn led_index(&self, x: usize, y: usize) -> Option<usize> {
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+        self.leds[y * self.width + x]
+    }
+}
+
+/// Where the LEDs showing cells sit on a physical strip longer than the grid.
+///
+/// The LEDs of the strip that show no cell, before, between or after the cells, are padding left blank.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct StripPadding {
+    /// The number of LEDs of the physical strip.
+    pub length: usize,
+    /// LEDs skipped at the start of the strip, before the first cell.
+    pub offset: usize,
+    /// The number of consecutive LEDs between two gaps, e.g. the width of a serpentine row. `0` for no gaps.
+    pub segment: usize,
+    /// LEDs skipped after each segment but the last one, e.g. LEDs bent around a corner between two rows.
+    pub gap: usize,
+}
+
+impl StripPadding {
+    /// Creates the padding of a strip of `length` LEDs whose first LEDs show the cells.
+    pub const fn new(length: usize) -> Self {
+        StripPadding {
+            length,
+            offset: 0,
+            segment: 0,
+            gap: 0,
+        }
+    }
+
+    /// Returns the position on the physical strip of the `led`-th LED showing a cell, or `None` past its end.
+    pub const fn physical_index(&self, led: usize) -> Option<usize> {
+        let gaps = match led.checked_div(self.segment) {
+            Some(gaps) => gaps,
+            None => 0,
+        };
+        let index = self.offset + led + gaps * self.gap;
+        if index < self.length {
+            Some(index)
+        } else {
+            None
+        }
+    }
+
+    /// Returns `true` if the strip is long enough for `leds` LEDs showing cells, e.g. in a compile-time assert.
+    pub const fn fits(&self, leds: usize) -> bool {
+        leds == 0 || self.physical_index(leds - 1).is_some()
+    }
+}
+
+/// A layout on a strip longer than the grid, see `StripPadding`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Padded<L> {
+    layout: L,
+    padding: StripPadding,
+}
+
+impl<L: LedLayout> Padded<L> {
+    /// Creates a layout skipping the padding LEDs of the strip.
+    ///
+    /// # Arguments
+    ///
+    /// * `layout`: The wiring of the LEDs showing cells, as if they were contiguous.
+    /// * `padding`: Where these LEDs sit on the physical strip.
+    pub const fn new(layout: L, padding: StripPadding) -> Self {
+        Padded { layout, padding }
+    }
+
+    /// Returns the padding of the strip.
+    pub const fn padding(&self) -> StripPadding {
+        self.padding
+    }
+}
+
+impl<L: LedLayout> LedLayout for Padded<L> {
+    fn width(&self) -> usize {
+        self.layout.width()
+    }
+
+    fn height(&self) -> usize {
+        self.layout.height()
+    }
+
+    fn led_index(&self, x: usize, y: usize) -> Option<usize> {
+        self.layout
+            .led_index(x, y)
+            .and_then(|led| self.padding.physical_index(led))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns the cell shown by each LED, panicking if a LED shows no cell.
+    fn strip<L: LedLayout>(layout: &L) -> Vec<(usize, usize)> {
+        let (width, height) = (layout.width(), layout.height());
+        let cells: Vec<_> = (0..height)
+            .flat_map(|y| (0..width).map(move |x| Some((x, y))))
+            .collect();
+        let mut leds = vec![None; width * height];
+        layout.arrange(&cells, &mut leds);
+        leds.into_iter().map(Option::unwrap).collect()
+    }
+
+    #[test]
+    fn row_major_is_identity() {
+        let leds = strip(&RowMajor::new(3, 2));
+        assert_eq!(leds, [(0, 0), (1, 0), (2, 0), (0, 1), (1, 1), (2, 1)]);
+        assert_eq!(RowMajor::new(3, 2).led_index(3, 0), None);
+    }
+
+    #[test]
+    fn serpentine_reverses_odd_rows() {
+        let leds = strip(&Serpentine::new(3, 3));
+        assert_eq!(
+            leds,
+            [
+                (0, 0),
+                (1, 0),
+                (2, 0),
+                (2, 1),
+                (1, 1),
+                (0, 1),
+                (0, 2),
+                (1, 2),
+                (2, 2)
+            ]
+        );
+    }
+
+    #[test]
+    fn column_serpentine_reverses_odd_columns() {
+        let leds = strip(&ColumnSerpentine::new(2, 3));
+        assert_eq!(leds, [(0, 0), (0, 1), (0, 2), (1, 2), (1, 1), (1, 0)]);
+    }
+
+    #[test]
+    fn transformed_layouts_are_bijective() {
+        for transform in Transform::ALL {
+            let layout = Transformed::new(Serpentine::new(4, 3), transform);
+            let mut leds = strip(&layout);
+            assert_eq!(leds.len(), 12);
+            leds.sort();
+            leds.dedup();
+            assert_eq!(leds.len(), 12, "{:?}", transform);
+        }
+    }
+
+    #[test]
+    fn transformed_layouts_move_the_first_led() {
+        let first_led = |transform| strip(&Transformed::new(Serpentine::new(4, 3), transform))[0];
+        assert_eq!(first_led(Transform::Identity), (0, 0));
+        assert_eq!(first_led(Transform::FlipHorizontal), (3, 0));
+        assert_eq!(first_led(Transform::FlipVertical), (0, 2));
+        assert_eq!(first_led(Transform::Rotate180), (3, 2));
+        // The grid is 3 x 4 once rotated: its bottom left corner lands on the top left of the strip.
+        assert_eq!(first_led(Transform::Rotate90), (0, 3));
+        assert_eq!(first_led(Transform::Rotate270), (2, 0));
+
+        let layout = Transformed::new(RowMajor::new(4, 3), Transform::Transpose);
+        assert_eq!((layout.width(), layout.height()), (3, 4));
+        assert_eq!(strip(&layout)[..3], [(0, 0), (0, 1), (0, 2)]);
+    }
+
+    #[test]
+    fn lookup_table_skips_missing_leds() {
+        const TABLE: [Option<usize>; 4] = [Some(3), None, Some(0), Some(1)];
+        let layout = LookupTable::new(2, 2, &TABLE);
+        let mut leds = ['-'; 4];
+        layout.arrange(&['a', 'b', 'c', 'd'], &mut leds);
+        assert_eq!(leds, ['c', 'd', '-', 'a']);
+    }
+
+    #[test]
+    fn padding_skips_offset_and_gaps() {
+        let padding = StripPadding {
+            length: 16,
+            offset: 2,
+            segment: 3,
+            gap: 1,
+        };
+        let layout = Padded::new(Serpentine::new(3, 3), padding);
+        let mut leds = ['-'; 16];
+        layout.arrange(&['a', 'b', 'c', 'd', 'e', 'f', 'g', 'h', 'i'], &mut leds);
+        assert_eq!(String::from_iter(leds), "--abc-fed-ghi---");
+
+        assert!(padding.fits(9));
+        assert!(!StripPadding {
+            length: 12,
+            ..padding
+        }
+        .fits(9));
+        assert_eq!(
+            Padded::new(
+                RowMajor::new(3, 3),
+                StripPadding {
+                    length: 12,
+                    ..padding
+                }
+            )
+            .led_index(2, 2),
+            None
+        );
+    }
+
+    #[test]
+    fn padding_without_gaps_keeps_the_cells_contiguous() {
+        let padding = StripPadding::new(144);
+        assert!(padding.fits(136));
+        assert!(!padding.fits(145));
+        assert_eq!(padding.physical_index(135), Some(135));
+    }
+}
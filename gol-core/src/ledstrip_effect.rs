@@ -0,0 +1,154 @@
+use cichlid::ColorRGB;
+
+use crate::color_space::ColorSpace;
+use crate::easing::Easing;
+
+/// The number of animation frames of a palette cross-fade, see `PALETTE_FADE_STEPS`.
+pub const TRANSITION_STEPS: usize = 50;
+/// The default global brightness of the ledstrip, see `OutputStage`.
+pub const MAX_BRIGHTNESS: u8 = 30;
+
+/// The number of generations over which the color of an alive cell shifts, older cells keep the last color.
+pub const AGE_STEPS: usize = 64;
+/// The number of generations a dead cell keeps glowing after its death.
+pub const GHOST_STEPS: usize = 8;
+/// The number of generations a newborn cell takes to fade from its flash to the alive color.
+pub const FLASH_STEPS: usize = 4;
+
+/// The easing of each type of transition of a LED.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TransitionEasing {
+    /// The transition of a cell becoming alive.
+    pub birth: Easing,
+    /// The transition of a cell dying.
+    pub death: Easing,
+    /// The transition of a cell keeping its state, whose color may still change with its age.
+    pub steady: Easing,
+}
+
+impl TransitionEasing {
+    /// Every transition at constant speed.
+    pub const LINEAR: TransitionEasing = TransitionEasing {
+        birth: Easing::Linear,
+        death: Easing::Linear,
+        steady: Easing::Linear,
+    };
+
+    /// Returns the eased progress of a transition at any frame, see `TransitionClock`.
+    ///
+    /// # Arguments
+    ///
+    /// * `was_alive`, `alive`: The previous and the new state of the cell, which select the curve.
+    /// * `t`: The elapsed part of the transition, from `0` to `255`.
+    pub const fn progress(&self, was_alive: bool, alive: bool, t: u8) -> u8 {
+        match (was_alive, alive) {
+            (false, true) => self.birth.ease(t),
+            (true, false) => self.death.ease(t),
+            _ => self.steady.ease(t),
+        }
+    }
+}
+
+impl Default for TransitionEasing {
+    /// Lingers at the dark end of births and deaths, where linear fades look abrupt.
+    fn default() -> Self {
+        TransitionEasing {
+            birth: Easing::QuadIn,
+            death: Easing::QuadOut,
+            steady: Easing::Linear,
+        }
+    }
+}
+
+/// The color space each type of transition of a LED is interpolated in.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct TransitionColorSpaces {
+    /// The transition of a cell becoming alive.
+    pub birth: ColorSpace,
+    /// The transition of a cell dying.
+    pub death: ColorSpace,
+    /// The transition of a cell keeping its state, whose color may still change with its age.
+    pub steady: ColorSpace,
+}
+
+impl TransitionColorSpaces {
+    /// Every transition in RGB.
+    pub const RGB: TransitionColorSpaces = TransitionColorSpaces {
+        birth: ColorSpace::Rgb,
+        death: ColorSpace::Rgb,
+        steady: ColorSpace::Rgb,
+    };
+
+    /// Returns the color space of a transition.
+    ///
+    /// # Arguments
+    ///
+    /// * `was_alive`, `alive`: The previous and the new state of the cell.
+    pub const fn space(&self, was_alive: bool, alive: bool) -> ColorSpace {
+        match (was_alive, alive) {
+            (false, true) => self.birth,
+            (true, false) => self.death,
+            _ => self.steady,
+        }
+    }
+
+    /// Mixes two colors in the color space of a transition, see `ColorSpace::mix`.
+    ///
+    /// # Arguments
+    ///
+    /// * `from`, `to`: The colors at the start and at the end of the transition.
+    /// * `was_alive`, `alive`: The previous and the new state of the cell.
+    /// * `amount`: The eased progress of the transition, from `0` to `255`.
+    pub fn mix(
+        &self,
+        from: ColorRGB,
+        to: ColorRGB,
+        was_alive: bool,
+        alive: bool,
+        amount: u8,
+    ) -> ColorRGB {
+        self.space(was_alive, alive).mix(from, to, amount)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn each_transition_follows_its_curve() {
+        let easing = TransitionEasing::default();
+        let t = u8::MAX / 4;
+        let linear = TransitionEasing::LINEAR.progress(true, true, t);
+        assert!(easing.progress(false, true, t) < linear);
+        assert!(easing.progress(true, false, t) > linear);
+        assert_eq!(easing.progress(false, false, t), linear);
+        for (was_alive, alive) in [(false, true), (true, false), (true, true)] {
+            assert_eq!(easing.progress(was_alive, alive, 0), 0);
+            assert_eq!(easing.progress(was_alive, alive, u8::MAX), u8::MAX);
+        }
+    }
+
+    #[test]
+    fn each_transition_picks_its_color_space() {
+        let spaces = TransitionColorSpaces {
+            birth: ColorSpace::Rgb,
+            death: ColorSpace::Hsv,
+            steady: ColorSpace::Oklab,
+        };
+        assert_eq!(spaces.space(false, true), ColorSpace::Rgb);
+        assert_eq!(spaces.space(true, false), ColorSpace::Hsv);
+        assert_eq!(spaces.space(false, false), ColorSpace::Oklab);
+
+        let (alive, dead) = (ColorRGB::BlueViolet, ColorRGB::Black);
+        assert_eq!(
+            spaces.mix(alive, dead, true, false, 128),
+            ColorSpace::Hsv.mix(alive, dead, 128)
+        );
+        assert_eq!(
+            spaces.mix(dead, alive, false, true, 128),
+            ColorSpace::Rgb.mix(dead, alive, 128)
+        );
+        assert_eq!(spaces.mix(alive, dead, true, false, u8::MAX), dead);
+    }
+}
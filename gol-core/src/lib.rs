@@ -0,0 +1,30 @@
+//! Hardware-independent core of pico-game-of-light.
+//!
+//! This crate holds the Game of Life simulation, the board hashing and the ledstrip color logic.
+//! It does not depend on any RP2040 peripheral: randomness is provided by the caller through
+//! `rand_core::RngCore` and logging is left to the firmware, so it builds both for `thumbv6m-none-eabi`
+//! and for the host, where it is unit tested with `cargo test-host`.
+#![cfg_attr(not(test), no_std)]
+
+pub mod bit_grid;
+pub mod cell_age;
+pub mod color_space;
+pub mod config;
+pub mod easing;
+pub mod export;
+pub mod frame;
+pub mod game;
+pub mod game_grid;
+pub mod hash;
+pub mod idle;
+pub mod layout;
+pub mod ledstrip_effect;
+pub mod output;
+pub mod palette;
+pub mod pattern;
+pub mod power;
+pub mod rng;
+pub mod rule;
+pub mod stagnation;
+pub mod timing;
+pub mod topology;
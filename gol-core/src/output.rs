@@ -0,0 +1,419 @@
+use cichlid::ColorRGB;
+
+use crate::power::{PowerEstimate, PowerModel};
+
+/// `1.0` in the 16.16 fixed point of the gamma computations.
+const ONE: u64 = 1 << 16;
+
+/// `2^(2^-k)` for `k` in `1..=16`, in 16.16 fixed point.
+const ROOTS_OF_TWO: [u64; 16] = [
+    92682, 77936, 71468, 68438, 66971, 66250, 65892, 65714, 65625, 65580, 65558, 65547, 65542,
+    65539, 65537, 65537,
+];
+
+/// The highest level of a `GammaTable`, one 8-bit step below `u16::MAX` so the dithering residual always fits.
+const MAX_LEVEL: u32 = (u8::MAX as u32) << 8;
+
+/// The gamma exponent of each channel, in 8.8 fixed point (`256` being `1.0`).
+///
+/// WS2812s are linear, while the eye is much more sensitive to changes among dim colors: decoding the colors with
+/// a gamma spends the output levels where they are visible.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Gamma {
+    pub r: u16,
+    pub g: u16,
+    pub b: u16,
+}
+
+impl Gamma {
+    /// Colors are sent as is.
+    pub const LINEAR: Gamma = Gamma::uniform(256);
+
+    /// A gamma of `2.2` on every channel, close to the sRGB curve the palettes are designed in.
+    pub const SRGB: Gamma = Gamma::uniform(563);
+
+    /// Uses the same exponent on every channel.
+    pub const fn uniform(exponent: u16) -> Self {
+        Gamma {
+            r: exponent,
+            g: exponent,
+            b: exponent,
+        }
+    }
+}
+
+impl Default for Gamma {
+    fn default() -> Self {
+        Self::SRGB
+    }
+}
+
+/// The lookup table of a gamma curve, from an 8-bit channel to a 16-bit linear level.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct GammaTable {
+    levels: [u16; 256],
+}
+
+impl GammaTable {
+    /// Generates the table of `(value / 255) ^ exponent`.
+    ///
+    /// # Arguments
+    ///
+    /// * `exponent`: The gamma, in 8.8 fixed point.
+    pub const fn new(exponent: u16) -> Self {
+        let mut levels = [0; 256];
+        let mut value = 1;
+        while value < levels.len() {
+            let x = value as u64 * ONE / u8::MAX as u64;
+            let y = log2(x) * exponent as i64 / 256;
+            levels[value] = (exp2(y) * MAX_LEVEL as u64 / ONE) as u16;
+            value += 1;
+        }
+        GammaTable { levels }
+    }
+
+    /// Returns the linear level of a channel, from `0` to `0xFF00`.
+    pub const fn level(&self, value: u8) -> u16 {
+        self.levels[value as usize]
+    }
+}
+
+/// The last stage before the ledstrip: gamma correction, global brightness and temporal dithering.
+///
+/// Colors are processed with 16 bits per channel, so dim fades keep their steps instead of collapsing into the few
+/// lowest 8-bit values. With dithering, the part of a level below the 8-bit output is carried over to the next
+/// frames of the LED, which then alternates between the two closest values, averaging to the exact level.
+///
+/// With a power limit, frames that would draw more current than the supply delivers are dimmed, see
+/// `with_power_limit`.
+///
+/// `N` is the number of LEDs of the strip, each of them keeping its own dithering residual.
+pub struct OutputStage<const N: usize> {
+    tables: [GammaTable; 3],
+    brightness: u8,
+    dithering: bool,
+    residuals: [[u8; 3]; N],
+    power: Option<PowerModel>,
+}
+
+impl<const N: usize> OutputStage<N> {
+    /// Creates an output stage.
+    ///
+    /// # Arguments
+    ///
+    /// * `gamma`: The gamma correction of each channel.
+    /// * `brightness`: The global brightness, `255` sending the corrected colors at full intensity.
+    /// * `dithering`: If set to `true`, colors are temporally dithered. It only smooths fades while frames keep
+    ///   being processed, at least every few tens of milliseconds.
+    pub const fn new(gamma: Gamma, brightness: u8, dithering: bool) -> Self {
+        OutputStage {
+            tables: [
+                GammaTable::new(gamma.r),
+                GammaTable::new(gamma.g),
+                GammaTable::new(gamma.b),
+            ],
+            brightness,
+            dithering,
+            residuals: [[0; 3]; N],
+            power: None,
+        }
+    }
+
+    /// Limits the current drawn by the frames to the supply of a power model.
+    pub const fn with_power_limit(mut self, power: PowerModel) -> Self {
+        self.power = Some(power);
+        self
+    }
+
+    /// Returns the global brightness.
+    pub fn brightness(&self) -> u8 {
+        self.brightness
+    }
+
+    /// Sets the global brightness, it applies from the next processed frame.
+    pub fn set_brightness(&mut self, brightness: u8) {
+        self.brightness = brightness;
+    }
+
+    /// Returns `true` if colors are temporally dithered.
+    pub fn dithering(&self) -> bool {
+        self.dithering
+    }
+
+    /// Enables or disables temporal dithering, forgetting the residuals of the previous frames.
+    pub fn set_dithering(&mut self, dithering: bool) {
+        self.dithering = dithering;
+        self.residuals = [[0; 3]; N];
+    }
+
+    /// Returns the power model limiting the frames, if any.
+    pub fn power_model(&self) -> Option<&PowerModel> {
+        self.power.as_ref()
+    }
+
+    /// Sets or removes the power model limiting the frames.
+    pub fn set_power_model(&mut self, power: Option<PowerModel>) {
+        self.power = power;
+    }
+
+    /// Computes the colors sent to the LEDs for one frame.
+    ///
+    /// # Arguments
+    ///
+    /// * `colors`: The colors of the frame, as designed in the palettes.
+    /// * `out`: Where to write the colors to send to the ledstrip.
+    ///
+    /// # Returns
+    ///
+    /// The estimated current of the frame, if a power model is set.
+    pub fn process(
+        &mut self,
+        colors: &[ColorRGB; N],
+        out: &mut [ColorRGB; N],
+    ) -> Option<PowerEstimate> {
+        let estimate = self.power.map(|power| self.limit(&power, colors));
+        let limit = estimate.map_or(ONE, |(_, limit)| limit);
+        (0..N).for_each(|led| {
+            let color = colors[led];
+            out[led] = ColorRGB {
+                r: self.channel(led, 0, color.r, limit),
+                g: self.channel(led, 1, color.g, limit),
+                b: self.channel(led, 2, color.b, limit),
+            };
+        });
+        estimate.map(|(estimate, _)| estimate)
+    }
+
+    /// Estimates the current of a frame and the factor dimming it within the supply limit.
+    ///
+    /// # Returns
+    ///
+    /// The estimate and the factor applied to the levels, in 16.16 fixed point.
+    fn limit(&self, power: &PowerModel, colors: &[ColorRGB; N]) -> (PowerEstimate, u64) {
+        let mut levels = [0; 3];
+        for color in colors {
+            levels[0] += self.level(0, color.r) as u64;
+            levels[1] += self.level(1, color.g) as u64;
+            levels[2] += self.level(2, color.b) as u64;
+        }
+        let (idle, budget) = (power.idle_ma(N), power.budget_ma(N) as u64);
+        let requested = power.weighted(levels);
+        let limit = if requested > budget * MAX_LEVEL as u64 {
+            budget * MAX_LEVEL as u64 * ONE / requested
+        } else {
+            ONE
+        };
+        let estimate = PowerEstimate {
+            requested_ma: idle + (requested / MAX_LEVEL as u64) as u32,
+            drawn_ma: idle + (requested * limit / ONE / MAX_LEVEL as u64) as u32,
+        };
+        (estimate, limit)
+    }
+
+    /// Returns the level of a channel at the global brightness, from `0` to `0xFF00`.
+    fn level(&self, channel: usize, value: u8) -> u32 {
+        self.tables[channel].level(value) as u32 * self.brightness as u32 / 255
+    }
+
+    fn channel(&mut self, led: usize, channel: usize, value: u8, limit: u64) -> u8 {
+        let level = (self.level(channel, value) as u64 * limit / ONE) as u32;
+        if !self.dithering {
+            return ((level + 0x80) >> 8) as u8;
+        }
+        let residual = &mut self.residuals[led][channel];
+        let level = level + *residual as u32;
+        *residual = (level & 0xFF) as u8;
+        (level >> 8) as u8
+    }
+}
+
+/// Computes the base 2 logarithm of `x`, in 16.16 fixed point, with `0 < x <= 1`.
+const fn log2(x: u64) -> i64 {
+    let mut x = x;
+    let mut log = 0;
+    while x < ONE {
+        x <<= 1;
+        log -= ONE as i64;
+    }
+    // x is in [1, 2), each squaring yields one more bit of the fractional part.
+    let mut bit = ONE as i64 >> 1;
+    while bit > 0 {
+        x = (x * x) >> 16;
+        if x >= 2 * ONE {
+            x >>= 1;
+            log += bit;
+        }
+        bit >>= 1;
+    }
+    log
+}
+
+/// Computes `2^y`, in 16.16 fixed point, with `y <= 0`.
+const fn exp2(y: i64) -> u64 {
+    let shift = -(y >> 16);
+    if shift >= 32 {
+        return 0;
+    }
+    let fraction = y & 0xFFFF;
+    let mut power = ONE;
+    let mut k = 0;
+    while k < ROOTS_OF_TWO.len() {
+        if fraction & (0x8000 >> k) != 0 {
+            power = (power * ROOTS_OF_TWO[k]) >> 16;
+        }
+        k += 1;
+    }
+    power >> shift
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gamma_tables_span_the_whole_range() {
+        for exponent in [128, 256, 563, 700] {
+            let table = GammaTable::new(exponent);
+            assert_eq!(table.level(0), 0);
+            assert!(table.level(u8::MAX) as u32 >= MAX_LEVEL - 2);
+            assert!((0..u8::MAX).all(|v| table.level(v) <= table.level(v + 1)));
+        }
+    }
+
+    #[test]
+    fn gamma_tables_follow_their_curve() {
+        let linear = GammaTable::new(256);
+        assert!((0..=u8::MAX).all(|v| linear.level(v).abs_diff(v as u16 * 256) <= 8));
+
+        // (128 / 255) ^ 2.2 = 0.2195
+        let srgb = GammaTable::new(Gamma::SRGB.r);
+        let expected = MAX_LEVEL * 2195 / 10000;
+        assert!((srgb.level(128) as u32).abs_diff(expected) < 200);
+        assert!(srgb.level(32) < linear.level(32) / 8);
+    }
+
+    #[test]
+    fn brightness_scales_the_output() {
+        let mut stage = OutputStage::<2>::new(Gamma::LINEAR, 255, false);
+        let colors = [ColorRGB::White, ColorRGB::BlueViolet];
+        let mut out = [ColorRGB::Black; 2];
+        stage.process(&colors, &mut out);
+        assert_eq!(out, colors);
+
+        stage.set_brightness(30);
+        stage.process(&colors, &mut out);
+        assert_eq!(
+            out[0],
+            ColorRGB {
+                r: 30,
+                g: 30,
+                b: 30
+            }
+        );
+        assert_eq!(stage.brightness(), 30);
+    }
+
+    #[test]
+    fn gamma_applies_per_channel() {
+        let gamma = Gamma {
+            r: 256,
+            g: 563,
+            b: 256,
+        };
+        let mut stage = OutputStage::<1>::new(gamma, 255, false);
+        let mut out = [ColorRGB::Black];
+        stage.process(
+            &[ColorRGB {
+                r: 128,
+                g: 128,
+                b: 0,
+            }],
+            &mut out,
+        );
+        assert_eq!(out[0].r, 128);
+        assert!((55..=57).contains(&out[0].g));
+        assert_eq!(out[0].b, 0);
+    }
+
+    #[test]
+    fn dithering_averages_to_the_exact_level() {
+        let mut stage = OutputStage::<1>::new(Gamma::SRGB, 30, true);
+        let color = ColorRGB {
+            r: 30,
+            g: 0,
+            b: 255,
+        };
+        let level = stage.tables[0].level(color.r) as u32 * 30 / 255;
+        let mut out = [ColorRGB::Black];
+        let mut sum = 0;
+        let frames = 256;
+        for _ in 0..frames {
+            stage.process(&[color], &mut out);
+            assert_eq!(out[0].b, 30);
+            sum += out[0].r as u32;
+        }
+        // Without dithering, the level would round down to black.
+        assert!(level < 0x80);
+        assert_eq!(sum, level * frames / 256);
+
+        stage.set_dithering(false);
+        stage.process(&[color], &mut out);
+        assert_eq!(out[0].r, 0);
+        assert!(!stage.dithering());
+    }
+
+    #[test]
+    fn frames_within_budget_are_untouched() {
+        let mut stage = OutputStage::<144>::new(Gamma::LINEAR, 255, false)
+            .with_power_limit(PowerModel::WS2812B);
+        let mut colors = [ColorRGB::Black; 144];
+        colors[..4].fill(ColorRGB::White);
+        let mut out = [ColorRGB::Black; 144];
+        let estimate = stage.process(&colors, &mut out).unwrap();
+        assert_eq!(out, colors);
+        assert_eq!(estimate.requested_ma, 144 + 4 * 42);
+        assert!(!estimate.is_limited());
+    }
+
+    #[test]
+    fn frames_over_budget_are_dimmed() {
+        let power = PowerModel::WS2812B;
+        let mut stage = OutputStage::<144>::new(Gamma::LINEAR, 255, true).with_power_limit(power);
+        let colors = [ColorRGB::White; 144];
+        let mut out = [ColorRGB::Black; 144];
+        let estimate = stage.process(&colors, &mut out).unwrap();
+        assert!(estimate.is_limited());
+        assert_eq!(estimate.requested_ma, 144 + 144 * 42);
+        assert!(estimate.drawn_ma <= power.supply_ma);
+        assert!(estimate.drawn_ma >= power.supply_ma - 2);
+        assert!(power.estimate(&out) <= power.supply_ma);
+        assert!(out.iter().all(|c| c.r == out[0].r && c.r > 0));
+
+        stage.set_power_model(None);
+        assert_eq!(stage.process(&colors, &mut out), None);
+        assert_eq!(out, colors);
+    }
+
+    #[test]
+    fn dithering_keeps_fades_smooth() {
+        // A dim fade goes through more average levels with dithering than without.
+        let average = |dithering| {
+            let mut stage = OutputStage::<1>::new(Gamma::SRGB, 30, dithering);
+            let mut out = [ColorRGB::Black];
+            let mut levels: Vec<u32> = (0..=u8::MAX)
+                .map(|v| {
+                    (0..64)
+                        .map(|_| {
+                            stage.process(&[ColorRGB { r: v, g: 0, b: 0 }], &mut out);
+                            out[0].r as u32
+                        })
+                        .sum()
+                })
+                .collect();
+            levels.dedup();
+            levels.len()
+        };
+        assert!(average(true) > 4 * average(false));
+    }
+}
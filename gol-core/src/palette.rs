@@ -0,0 +1,415 @@
+use cichlid::ColorRGB;
+
+use crate::cell_age::CellAge;
+use crate::ledstrip_effect::{AGE_STEPS, FLASH_STEPS, GHOST_STEPS};
+
+/// A color at a given position of a `Gradient`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct GradientStop {
+    /// Position of the stop along the gradient, from `0` to `255`.
+    pub position: u8,
+    pub color: ColorRGB,
+}
+
+impl GradientStop {
+    pub const fn new(position: u8, color: ColorRGB) -> Self {
+        GradientStop { position, color }
+    }
+}
+
+/// A multi-stop color gradient, linearly interpolated between its stops.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Gradient<'a> {
+    stops: &'a [GradientStop],
+}
+
+impl<'a> Gradient<'a> {
+    /// Creates a gradient from its stops.
+    ///
+    /// # Panics
+    ///
+    /// If `stops` is empty or not sorted by position, which fails the build in a `const`.
+    pub const fn new(stops: &'a [GradientStop]) -> Self {
+        assert!(!stops.is_empty(), "a gradient needs at least one stop");
+        let mut i = 1;
+        while i < stops.len() {
+            assert!(
+                stops[i - 1].position <= stops[i].position,
+                "gradient stops must be sorted by position"
+            );
+            i += 1;
+        }
+        Gradient { stops }
+    }
+
+    /// Returns the color at `position`, the first and last stops extending to both ends of the gradient.
+    pub fn sample(&self, position: u8) -> ColorRGB {
+        let first = self.stops[0];
+        if position <= first.position {
+            return first.color;
+        }
+        for pair in self.stops.windows(2) {
+            let (from, to) = (pair[0], pair[1]);
+            if position <= to.position {
+                let span = (to.position - from.position) as u16;
+                let amount = (position - from.position) as u16 * 255 / span;
+                return mix(from.color, to.color, amount as u8);
+            }
+        }
+        self.stops[self.stops.len() - 1].color
+    }
+}
+
+/// What picks the position of an alive cell along the gradient of a `Palette`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PaletteMapping {
+    /// Cells move along the gradient as they get older, over `AGE_STEPS` generations.
+    Age,
+    /// Each column takes a position along the gradient, from the left to the right edge of the grid.
+    Column,
+}
+
+/// The colors of the cells: a gradient for alive cells, a color for dead ones,
+/// a flash for newborns and a fading ghost trail after a death.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Palette<'a> {
+    /// The colors of alive cells.
+    pub alive: Gradient<'a>,
+    /// What picks the position of an alive cell along `alive`.
+    pub mapping: PaletteMapping,
+    /// The color of dead cells.
+    pub dead: ColorRGB,
+    /// The color of newborn cells, fading to their alive color over `FLASH_STEPS` generations.
+    pub flash: ColorRGB,
+}
+
+const CLASSIC_STOPS: [GradientStop; 2] = [
+    GradientStop::new(0, ColorRGB::BlueViolet),
+    GradientStop::new(255, rgb(0x00, 0x80, 0x80)),
+];
+
+const FIRE_STOPS: [GradientStop; 3] = [
+    GradientStop::new(0, rgb(255, 200, 0)),
+    GradientStop::new(96, rgb(255, 80, 0)),
+    GradientStop::new(255, rgb(160, 0, 0)),
+];
+
+const OCEAN_STOPS: [GradientStop; 3] = [
+    GradientStop::new(0, rgb(0, 255, 200)),
+    GradientStop::new(128, rgb(0, 100, 255)),
+    GradientStop::new(255, rgb(0, 0, 120)),
+];
+
+const FOREST_STOPS: [GradientStop; 3] = [
+    GradientStop::new(0, rgb(160, 255, 0)),
+    GradientStop::new(128, rgb(0, 180, 30)),
+    GradientStop::new(255, rgb(60, 40, 0)),
+];
+
+/// The hue circle, stopping at magenta so both edges of the grid do not share the same red.
+const RAINBOW_STOPS: [GradientStop; 6] = [
+    GradientStop::new(0, rgb(255, 0, 0)),
+    GradientStop::new(51, rgb(255, 255, 0)),
+    GradientStop::new(102, rgb(0, 255, 0)),
+    GradientStop::new(153, rgb(0, 255, 255)),
+    GradientStop::new(204, rgb(0, 0, 255)),
+    GradientStop::new(255, rgb(255, 0, 255)),
+];
+
+const MONOCHROME_STOPS: [GradientStop; 1] = [GradientStop::new(0, ColorRGB::White)];
+
+impl Palette<'static> {
+    /// BlueViolet cells slowly turning teal as they age, on a black background.
+    pub const CLASSIC: Palette<'static> = Palette {
+        alive: Gradient::new(&CLASSIC_STOPS),
+        mapping: PaletteMapping::Age,
+        dead: ColorRGB::Black,
+        flash: ColorRGB::White,
+    };
+
+    /// Yellow newborns cooling down to embers.
+    pub const FIRE: Palette<'static> = Palette {
+        alive: Gradient::new(&FIRE_STOPS),
+        mapping: PaletteMapping::Age,
+        dead: ColorRGB::Black,
+        flash: ColorRGB::White,
+    };
+
+    /// Cyan cells sinking to deep blue.
+    pub const OCEAN: Palette<'static> = Palette {
+        alive: Gradient::new(&OCEAN_STOPS),
+        mapping: PaletteMapping::Age,
+        dead: ColorRGB::Black,
+        flash: ColorRGB::White,
+    };
+
+    /// Fresh green cells withering to brown.
+    pub const FOREST: Palette<'static> = Palette {
+        alive: Gradient::new(&FOREST_STOPS),
+        mapping: PaletteMapping::Age,
+        dead: ColorRGB::Black,
+        flash: ColorRGB::White,
+    };
+
+    /// Each column of the grid takes its own hue.
+    pub const RAINBOW_BY_COLUMN: Palette<'static> = Palette {
+        alive: Gradient::new(&RAINBOW_STOPS),
+        mapping: PaletteMapping::Column,
+        dead: ColorRGB::Black,
+        flash: ColorRGB::White,
+    };
+
+    /// White cells, the age only showing through the newborn flash and the ghost trails.
+    pub const MONOCHROME: Palette<'static> = Palette {
+        alive: Gradient::new(&MONOCHROME_STOPS),
+        mapping: PaletteMapping::Age,
+        dead: ColorRGB::Black,
+        flash: ColorRGB::White,
+    };
+
+    /// Every built-in palette with its name.
+    pub const ALL: [(&'static str, Palette<'static>); 6] = [
+        ("classic", Palette::CLASSIC),
+        ("fire", Palette::FIRE),
+        ("ocean", Palette::OCEAN),
+        ("forest", Palette::FOREST),
+        ("rainbow-by-column", Palette::RAINBOW_BY_COLUMN),
+        ("monochrome", Palette::MONOCHROME),
+    ];
+}
+
+impl Palette<'_> {
+    /// Returns the color of a cell, at full intensity: brightness is left to the `OutputStage`.
+    ///
+    /// # Arguments
+    ///
+    /// * `age`: How long the cell has been alive or dead, see `CellAges`.
+    /// * `column`, `width`: The column of the cell and the width of the grid, used by `PaletteMapping::Column`.
+    pub fn color(&self, age: CellAge, column: usize, width: usize) -> ColorRGB {
+        match age {
+            CellAge::Alive { generations } => {
+                let generations = generations as usize;
+                let alive = self.alive_color(generations, column, width);
+                if generations < FLASH_STEPS {
+                    mix(self.flash, alive, (generations * 255 / FLASH_STEPS) as u8)
+                } else {
+                    alive
+                }
+            }
+            CellAge::Dead { generations } if (generations as usize) < GHOST_STEPS => {
+                // Ghosts start half way between the alive and the dead color.
+                let fade = 128 + generations as usize * 127 / (GHOST_STEPS - 1);
+                mix(self.alive_color(0, column, width), self.dead, fade as u8)
+            }
+            CellAge::Dead { .. } => self.dead,
+        }
+    }
+
+    /// Returns the color of an alive or dead cell regardless of its age, at full intensity.
+    pub fn state_color(&self, alive: bool, column: usize, width: usize) -> ColorRGB {
+        if alive {
+            self.alive_color(0, column, width)
+        } else {
+            self.dead
+        }
+    }
+
+    fn alive_color(&self, generations: usize, column: usize, width: usize) -> ColorRGB {
+        let position = match self.mapping {
+            PaletteMapping::Age => generations.min(AGE_STEPS - 1) * 255 / (AGE_STEPS - 1),
+            PaletteMapping::Column => {
+                column.min(width.saturating_sub(1)) * 255 / width.saturating_sub(1).max(1)
+            }
+        };
+        self.alive.sample(position as u8)
+    }
+}
+
+impl Default for Palette<'static> {
+    fn default() -> Self {
+        Self::CLASSIC
+    }
+}
+
+/// A cross-fade from one palette to another, so the palette can be switched without a sudden jump.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CrossFade<'a> {
+    from: Palette<'a>,
+    to: Palette<'a>,
+    /// Number of steps already done.
+    step: usize,
+    /// Number of steps of a fade.
+    steps: usize,
+}
+
+impl<'a> CrossFade<'a> {
+    /// Creates a cross-fade showing `palette`.
+    ///
+    /// # Arguments
+    ///
+    /// * `palette`: The palette shown until the first `switch_to`.
+    /// * `steps`: The number of calls to `advance` a fade lasts.
+    pub const fn new(palette: Palette<'a>, steps: usize) -> Self {
+        CrossFade {
+            from: palette,
+            to: palette,
+            step: steps,
+            steps,
+        }
+    }
+
+    /// Starts fading to `palette`, unless it is already the target.
+    ///
+    /// A switch in the middle of a fade starts the new fade from the previous target.
+    ///
+    /// # Returns
+    ///
+    /// Returns `true` if a new fade started.
+    pub fn switch_to(&mut self, palette: Palette<'a>) -> bool {
+        if palette == self.to {
+            return false;
+        }
+        self.from = self.to;
+        self.to = palette;
+        self.step = 0;
+        true
+    }
+
+    /// Moves the fade one step forward.
+    pub fn advance(&mut self) {
+        self.step = (self.step + 1).min(self.steps);
+    }
+
+    /// Returns `true` until the fade is done.
+    pub fn is_fading(&self) -> bool {
+        self.step < self.steps
+    }
+
+    /// Returns the palette faded from.
+    pub fn from(&self) -> &Palette<'a> {
+        &self.from
+    }
+
+    /// Returns the palette faded to.
+    pub fn to(&self) -> &Palette<'a> {
+        &self.to
+    }
+
+    /// Mixes the colors of a LED in both palettes according to the progress of the fade.
+    ///
+    /// # Arguments
+    ///
+    /// * `color`: Computes the color of the LED with a given palette.
+    pub fn color(&self, color: impl Fn(&Palette<'a>) -> ColorRGB) -> ColorRGB {
+        if !self.is_fading() {
+            return color(&self.to);
+        }
+        let amount = self.step * 255 / self.steps;
+        mix(color(&self.from), color(&self.to), amount as u8)
+    }
+}
+
+/// Linearly mixes two colors, `amount` going from `0` (only `from`) to `255` (only `to`).
+pub fn mix(from: ColorRGB, to: ColorRGB, amount: u8) -> ColorRGB {
+    let channel =
+        |a: u8, b: u8| (a as i32 + (b as i32 - a as i32) * amount as i32 / u8::MAX as i32) as u8;
+    ColorRGB {
+        r: channel(from.r, to.r),
+        g: channel(from.g, to.g),
+        b: channel(from.b, to.b),
+    }
+}
+
+const fn rgb(r: u8, g: u8, b: u8) -> ColorRGB {
+    ColorRGB { r, g, b }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RED: ColorRGB = rgb(255, 0, 0);
+    const BLUE: ColorRGB = rgb(0, 0, 255);
+
+    #[test]
+    fn gradient_interpolates_between_stops() {
+        const STOPS: [GradientStop; 3] = [
+            GradientStop::new(64, RED),
+            GradientStop::new(128, BLUE),
+            GradientStop::new(192, RED),
+        ];
+        let gradient = Gradient::new(&STOPS);
+        assert_eq!(gradient.sample(0), RED);
+        assert_eq!(gradient.sample(64), RED);
+        assert_eq!(gradient.sample(96), rgb(128, 0, 127));
+        assert_eq!(gradient.sample(128), BLUE);
+        assert_eq!(gradient.sample(255), RED);
+    }
+
+    #[test]
+    fn single_stop_gradient_is_constant() {
+        let gradient = Palette::MONOCHROME.alive;
+        assert_eq!(gradient.sample(0), ColorRGB::White);
+        assert_eq!(gradient.sample(255), ColorRGB::White);
+    }
+
+    #[test]
+    fn builtin_palettes_flash_then_fade_to_dead() {
+        for (name, palette) in Palette::ALL {
+            let newborn = palette.color(CellAge::Alive { generations: 0 }, 0, 17);
+            assert_eq!(newborn, palette.flash, "{}", name);
+            let never_alive = palette.color(CellAge::NEVER_ALIVE, 0, 17);
+            assert_eq!(never_alive, palette.dead, "{}", name);
+            let ghost = palette.color(CellAge::Dead { generations: 0 }, 0, 17);
+            assert_ne!(ghost, never_alive, "{}", name);
+        }
+    }
+
+    #[test]
+    fn age_mapping_shifts_colors() {
+        let palette = Palette::CLASSIC;
+        let young = palette.color(CellAge::Alive { generations: 4 }, 0, 17);
+        let old = palette.color(CellAge::Alive { generations: 200 }, 0, 17);
+        assert_ne!(young, old);
+        assert_eq!(old, CLASSIC_STOPS[1].color);
+        assert_eq!(palette.state_color(true, 5, 17), ColorRGB::BlueViolet);
+    }
+
+    #[test]
+    fn column_mapping_spans_the_grid() {
+        let palette = Palette::RAINBOW_BY_COLUMN;
+        let old = CellAge::Alive { generations: 100 };
+        assert_eq!(palette.color(old, 0, 17), RED);
+        assert_eq!(palette.color(old, 16, 17), rgb(255, 0, 255));
+        assert_eq!(palette.color(old, 0, 1), RED);
+    }
+
+    #[test]
+    fn cross_fade_mixes_palettes() {
+        let mut fade = CrossFade::new(Palette::FIRE, 4);
+        let color = |palette: &Palette| palette.state_color(true, 0, 17);
+        assert!(!fade.is_fading());
+        assert!(!fade.switch_to(Palette::FIRE));
+        assert_eq!(fade.color(color), color(&Palette::FIRE));
+
+        assert!(fade.switch_to(Palette::OCEAN));
+        assert_eq!(fade.color(color), color(&Palette::FIRE));
+        fade.advance();
+        fade.advance();
+        assert_eq!(
+            fade.color(color),
+            mix(color(&Palette::FIRE), color(&Palette::OCEAN), 127)
+        );
+        fade.advance();
+        fade.advance();
+        fade.advance();
+        assert!(!fade.is_fading());
+        assert_eq!(fade.color(color), color(&Palette::OCEAN));
+    }
+
+    #[test]
+    fn mix_endpoints() {
+        assert_eq!(mix(RED, BLUE, 0), RED);
+        assert_eq!(mix(RED, BLUE, 255), BLUE);
+    }
+}
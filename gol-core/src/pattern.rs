@@ -0,0 +1,830 @@
+use core::fmt;
+
+use crate::rule::{ParseRuleError, Rule};
+
+/// A Life pattern, described in the run length encoded (RLE), plaintext (`.cells`) or Life 1.06 format.
+///
+/// The pattern borrows its text and decodes the cells on the fly, so it needs no allocation.
+/// Parsing is a `const fn`, which validates bundled patterns at compile time:
+///
+/// ```
+/// use gol_core::pattern::Pattern;
+///
+/// const BLINKER: Pattern = match Pattern::parse("x = 3, y = 1\n3o!") {
+///     Ok(pattern) => pattern,
+///     Err(_) => panic!("invalid RLE"),
+/// };
+/// assert_eq!(BLINKER.cells().count(), 3);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Pattern<'a> {
+    /// The whole text of the pattern.
+    text: &'a [u8],
+    format: Format,
+    /// Index of the first byte after the header line.
+    body_start: usize,
+    width: usize,
+    height: usize,
+    rule: Option<Rule>,
+    /// Position of the top left corner of the pattern in the coordinates of the text.
+    offset: (isize, isize),
+}
+
+/// The text format a `Pattern` was parsed from.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Format {
+    Rle,
+    Plaintext,
+    Life106,
+}
+
+/// Errors returned while parsing a pattern.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ParsePatternError {
+    /// There is no `x = .., y = ..` header line.
+    MissingHeader,
+    /// The header line is malformed.
+    InvalidHeader,
+    /// The rule of the header is not a valid B/S rulestring.
+    InvalidRule(ParseRuleError),
+    /// A row holds more cells than the width declared in the header.
+    TooWide,
+    /// The pattern holds more rows than the height declared in the header.
+    TooHigh,
+    /// A character of the pattern body is not a run count nor a tag.
+    InvalidCharacter(char),
+    /// A Life 1.06 line is not a pair of integer coordinates.
+    InvalidCoordinates,
+}
+
+/// A symmetry of the square, applied to a pattern when stamping it on a grid.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Transform {
+    /// The pattern as described.
+    #[default]
+    Identity,
+    /// Rotated a quarter turn clockwise.
+    Rotate90,
+    /// Rotated a half turn.
+    Rotate180,
+    /// Rotated a quarter turn counterclockwise.
+    Rotate270,
+    /// Mirrored left to right.
+    FlipHorizontal,
+    /// Mirrored top to bottom.
+    FlipVertical,
+    /// Mirrored along the top left to bottom right diagonal.
+    Transpose,
+    /// Mirrored along the top right to bottom left diagonal.
+    AntiTranspose,
+}
+
+impl<'a> Pattern<'a> {
+    /// Parses a pattern in the RLE format.
+    ///
+    /// Leading `#` comment lines are skipped, then comes the `x = <width>, y = <height>` header, optionally
+    /// followed by `, rule = <B/S rulestring>`. The body is a sequence of `<count><tag>` runs, where `b` is a dead
+    /// cell, `o` an alive one, `$` the end of a row and `!` the end of the pattern.
+    ///
+    /// # Returns
+    ///
+    /// The parsed pattern, or the reason why the text is not a valid RLE pattern.
+    pub const fn parse(rle: &'a str) -> Result<Pattern<'a>, ParsePatternError> {
+        let b = rle.as_bytes();
+
+        // Comment lines
+        let mut i = skip_whitespace(b, 0);
+        while i < b.len() && b[i] == b'#' {
+            i = skip_whitespace(b, next_line(b, i));
+        }
+
+        // Header line
+        if i >= b.len() || b[i] != b'x' {
+            return Err(ParsePatternError::MissingHeader);
+        }
+        let (i, width) = match header_value(b, i, b'x') {
+            Ok(value) => value,
+            Err(e) => return Err(e),
+        };
+        let i = match expect(b, i, b',') {
+            Ok(i) => i,
+            Err(e) => return Err(e),
+        };
+        let (mut i, height) = match header_value(b, i, b'y') {
+            Ok(value) => value,
+            Err(e) => return Err(e),
+        };
+        let mut rule = None;
+        if i < b.len() && b[i] == b',' {
+            i = skip_blanks(b, i + 1);
+            let keyword = b"rule";
+            let mut k = 0;
+            while k < keyword.len() {
+                if i + k >= b.len() || b[i + k] != keyword[k] {
+                    return Err(ParsePatternError::InvalidHeader);
+                }
+                k += 1;
+            }
+            i = match expect(b, i + keyword.len(), b'=') {
+                Ok(i) => i,
+                Err(e) => return Err(e),
+            };
+            let start = i;
+            while i < b.len() && !is_whitespace(b[i]) {
+                i += 1;
+            }
+            rule = match Rule::parse_bytes(b, start, i) {
+                Ok(rule) => Some(rule),
+                Err(e) => return Err(ParsePatternError::InvalidRule(e)),
+            };
+            i = skip_blanks(b, i);
+        }
+        if i < b.len() && b[i] != b'\n' && b[i] != b'\r' {
+            return Err(ParsePatternError::InvalidHeader);
+        }
+
+        let pattern = Pattern {
+            text: b,
+            format: Format::Rle,
+            body_start: i,
+            width,
+            height,
+            rule,
+            offset: (0, 0),
+        };
+
+        // Body, decoded once to check it fits in the declared dimensions.
+        let (mut i, mut x, mut y): (usize, usize, usize) = (i, 0, 0);
+        loop {
+            let (next, count, tag) = match next_run(b, i) {
+                Ok(run) => run,
+                Err(e) => return Err(e),
+            };
+            i = next;
+            match tag {
+                b'!' => break,
+                b'$' => {
+                    x = 0;
+                    y = match y.checked_add(count) {
+                        Some(y) if y < height => y,
+                        _ => return Err(ParsePatternError::TooHigh),
+                    };
+                }
+                _ => {
+                    x = match x.checked_add(count) {
+                        Some(x) if x <= width => x,
+                        _ => return Err(ParsePatternError::TooWide),
+                    };
+                    if y >= height {
+                        return Err(ParsePatternError::TooHigh);
+                    }
+                }
+            }
+        }
+        Ok(pattern)
+    }
+
+    /// Parses a pattern in the plaintext (`.cells`) format.
+    ///
+    /// Lines starting with `!` are comments, every other line is a row where `.` is a dead cell and `O` (or `*`)
+    /// an alive one. Rows may have different lengths, the width of the pattern is the one of the longest row.
+    ///
+    /// # Returns
+    ///
+    /// The parsed pattern, or the reason why the text is not a valid plaintext pattern.
+    pub const fn parse_plaintext(text: &'a str) -> Result<Pattern<'a>, ParsePatternError> {
+        let b = text.as_bytes();
+        let (mut i, mut x, mut width, mut height) = (0, 0, 0, 0);
+        while i < b.len() {
+            let c = b[i];
+            // `x == 0` until the first cell of a line.
+            if x == 0 && c == b'!' {
+                i = next_line(b, i);
+                continue;
+            }
+            match c {
+                b'\n' => {
+                    height += 1;
+                    x = 0;
+                }
+                b'.' | b'O' | b'*' => {
+                    x += 1;
+                    if x > width {
+                        width = x;
+                    }
+                }
+                b'\r' => {}
+                _ => return Err(ParsePatternError::InvalidCharacter(c as char)),
+            }
+            i += 1;
+        }
+        if x > 0 {
+            height += 1;
+        }
+        Ok(Pattern {
+            text: b,
+            format: Format::Plaintext,
+            body_start: 0,
+            width,
+            height,
+            rule: None,
+            offset: (0, 0),
+        })
+    }
+
+    /// Parses a pattern in the Life 1.06 format.
+    ///
+    /// After the `#Life 1.06` header, each line holds the `x y` coordinates of an alive cell. Coordinates may be
+    /// negative: the pattern is the bounding box of the cells, whose top left corner is given by `offset`.
+    ///
+    /// # Returns
+    ///
+    /// The parsed pattern, or the reason why the text is not a valid Life 1.06 pattern.
+    pub const fn parse_life106(text: &'a str) -> Result<Pattern<'a>, ParsePatternError> {
+        let b = text.as_bytes();
+        let header = b"#Life 1.06";
+        let mut i = skip_whitespace(b, 0);
+        let mut k = 0;
+        while k < header.len() {
+            if i + k >= b.len() || b[i + k] != header[k] {
+                return Err(ParsePatternError::MissingHeader);
+            }
+            k += 1;
+        }
+        i = next_line(b, i);
+        let body_start = i;
+
+        let (mut min_x, mut min_y, mut max_x, mut max_y) =
+            (isize::MAX, isize::MAX, isize::MIN, isize::MIN);
+        loop {
+            let (next, x, y) = match next_coordinates(b, i) {
+                Ok(Some(cell)) => cell,
+                Ok(None) => break,
+                Err(e) => return Err(e),
+            };
+            i = next;
+            if x < min_x {
+                min_x = x;
+            }
+            if x > max_x {
+                max_x = x;
+            }
+            if y < min_y {
+                min_y = y;
+            }
+            if y > max_y {
+                max_y = y;
+            }
+        }
+
+        let (width, height, offset) = if max_x < min_x {
+            (0, 0, (0, 0))
+        } else {
+            (
+                max_x.abs_diff(min_x) + 1,
+                max_y.abs_diff(min_y) + 1,
+                (min_x, min_y),
+            )
+        };
+        Ok(Pattern {
+            text: b,
+            format: Format::Life106,
+            body_start,
+            width,
+            height,
+            rule: None,
+            offset,
+        })
+    }
+
+    /// Returns the width of the pattern: declared in the header of an RLE pattern, measured from the longest row of a
+    /// plaintext one and from the span of the coordinates of a Life 1.06 one.
+    pub const fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Returns the height of the pattern: declared in the header of an RLE pattern, measured from the number of rows of
+    /// a plaintext one and from the span of the coordinates of a Life 1.06 one.
+    pub const fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Returns the rule of the pattern header, if any.
+    pub const fn rule(&self) -> Option<Rule> {
+        self.rule
+    }
+
+    /// Returns the position of the top left corner of the pattern in the coordinates used by its text.
+    ///
+    /// Only Life 1.06 patterns carry coordinates, it is always `(0, 0)` for the other formats.
+    pub const fn offset(&self) -> (isize, isize) {
+        self.offset
+    }
+
+    /// Iterates over the positions of the alive cells, relative to the top left corner of the pattern.
+    ///
+    /// RLE and plaintext cells come row by row, Life 1.06 ones in the order of the text.
+    pub fn cells(&self) -> PatternCells<'a> {
+        PatternCells {
+            text: self.text,
+            format: self.format,
+            offset: self.offset,
+            i: self.body_start,
+            x: 0,
+            y: 0,
+            alive_run: 0,
+        }
+    }
+}
+
+/// Iterator over the alive cells of a `Pattern`, see `Pattern::cells`.
+pub struct PatternCells<'a> {
+    text: &'a [u8],
+    format: Format,
+    offset: (isize, isize),
+    /// Index of the next byte to decode.
+    i: usize,
+    x: usize,
+    y: usize,
+    /// Number of alive cells left in the current run.
+    alive_run: usize,
+}
+
+impl Iterator for PatternCells<'_> {
+    type Item = (usize, usize);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // The text was validated when parsing the pattern, errors cannot happen.
+        match self.format {
+            Format::Rle => self.next_rle(),
+            Format::Plaintext => self.next_plaintext(),
+            Format::Life106 => {
+                let (next, x, y) = next_coordinates(self.text, self.i).ok()??;
+                self.i = next;
+                Some((x.abs_diff(self.offset.0), y.abs_diff(self.offset.1)))
+            }
+        }
+    }
+}
+
+impl PatternCells<'_> {
+    fn next_rle(&mut self) -> Option<(usize, usize)> {
+        while self.alive_run == 0 {
+            let (next, count, tag) = next_run(self.text, self.i).ok()?;
+            self.i = next;
+            match tag {
+                b'!' => return None,
+                b'$' => {
+                    self.x = 0;
+                    self.y = self.y.checked_add(count)?;
+                }
+                _ if is_alive_tag(tag) => self.alive_run = count,
+                _ => self.x = self.x.checked_add(count)?,
+            }
+        }
+        self.alive_run -= 1;
+        self.x += 1;
+        Some((self.x - 1, self.y))
+    }
+
+    fn next_plaintext(&mut self) -> Option<(usize, usize)> {
+        while self.i < self.text.len() {
+            let c = self.text[self.i];
+            if self.x == 0 && c == b'!' {
+                self.i = next_line(self.text, self.i);
+                continue;
+            }
+            self.i += 1;
+            match c {
+                b'\n' => {
+                    self.x = 0;
+                    self.y += 1;
+                }
+                b'O' | b'*' => {
+                    self.x += 1;
+                    return Some((self.x - 1, self.y));
+                }
+                b'.' => self.x += 1,
+                _ => {}
+            }
+        }
+        None
+    }
+}
+
+impl Transform {
+    /// All the transforms, e.g. to try every orientation of a pattern.
+    pub const ALL: [Transform; 8] = [
+        Transform::Identity,
+        Transform::Rotate90,
+        Transform::Rotate180,
+        Transform::Rotate270,
+        Transform::FlipHorizontal,
+        Transform::FlipVertical,
+        Transform::Transpose,
+        Transform::AntiTranspose,
+    ];
+
+    /// Computes the dimensions of a `width` x `height` box once transformed.
+    pub const fn dimensions(self, width: usize, height: usize) -> (usize, usize) {
+        match self {
+            Transform::Identity
+            | Transform::Rotate180
+            | Transform::FlipHorizontal
+            | Transform::FlipVertical => (width, height),
+            Transform::Rotate90
+            | Transform::Rotate270
+            | Transform::Transpose
+            | Transform::AntiTranspose => (height, width),
+        }
+    }
+
+    /// Moves the cell at `(x, y)` in a `width` x `height` box to its transformed position.
+    ///
+    /// The result stays in the top left corner of the transformed box, see `dimensions`.
+    pub const fn apply(self, x: usize, y: usize, width: usize, height: usize) -> (usize, usize) {
+        match self {
+            Transform::Identity => (x, y),
+            Transform::Rotate90 => (height - 1 - y, x),
+            Transform::Rotate180 => (width - 1 - x, height - 1 - y),
+            Transform::Rotate270 => (y, width - 1 - x),
+            Transform::FlipHorizontal => (width - 1 - x, y),
+            Transform::FlipVertical => (x, height - 1 - y),
+            Transform::Transpose => (y, x),
+            Transform::AntiTranspose => (height - 1 - y, width - 1 - x),
+        }
+    }
+}
+
+impl fmt::Display for ParsePatternError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParsePatternError::MissingHeader => f.write_str("missing 'x = .., y = ..' header"),
+            ParsePatternError::InvalidHeader => f.write_str("invalid header line"),
+            ParsePatternError::InvalidRule(e) => write!(f, "invalid rule: {}", e),
+            ParsePatternError::TooWide => f.write_str("row wider than the declared width"),
+            ParsePatternError::TooHigh => f.write_str("more rows than the declared height"),
+            ParsePatternError::InvalidCharacter(c) => write!(f, "invalid character '{}'", c),
+            ParsePatternError::InvalidCoordinates => f.write_str("invalid cell coordinates"),
+        }
+    }
+}
+
+/// Classic patterns, validated at compile time.
+pub mod classics {
+    use super::{ParsePatternError, Pattern};
+
+    /// The smallest spaceship, moving one cell diagonally every 4 generations.
+    pub const GLIDER: Pattern<'static> = bundled(Pattern::parse(
+        "#N Glider
+x = 3, y = 3, rule = B3/S23
+bob$2bo$3o!",
+    ));
+
+    /// The lightweight spaceship, moving two cells horizontally every 4 generations.
+    pub const LWSS: Pattern<'static> = bundled(Pattern::parse(
+        "#N Lightweight spaceship
+x = 5, y = 4, rule = B3/S23
+bo2bo$o4b$o3bo$4o!",
+    ));
+
+    /// A period 3 oscillator.
+    pub const PULSAR: Pattern<'static> = bundled(Pattern::parse(
+        "#N Pulsar
+x = 13, y = 13, rule = B3/S23
+2b3o3b3o2b2$o4bobo4bo$o4bobo4bo$o4bobo4bo$2b3o3b3o2b2$2b3o3b3o2b$o4bobo4bo$o4bobo4bo$o4bobo4bo2$
+2b3o3b3o!",
+    ));
+
+    /// A methuselah of 5 cells that stabilizes after 1103 generations.
+    pub const R_PENTOMINO: Pattern<'static> = bundled(Pattern::parse(
+        "#N R-pentomino
+x = 3, y = 3, rule = B3/S23
+b2o$2ob$bo!",
+    ));
+
+    /// A methuselah of 7 cells that stabilizes after 5206 generations.
+    pub const ACORN: Pattern<'static> = bundled(Pattern::parse(
+        "#N Acorn
+x = 7, y = 3, rule = B3/S23
+bo5b$3bo3b$2o2b3o!",
+    ));
+
+    /// Every bundled pattern with its name.
+    pub const ALL: [(&str, Pattern<'static>); 5] = [
+        ("glider", GLIDER),
+        ("lwss", LWSS),
+        ("pulsar", PULSAR),
+        ("r-pentomino", R_PENTOMINO),
+        ("acorn", ACORN),
+    ];
+
+    const fn bundled(parsed: Result<Pattern<'static>, ParsePatternError>) -> Pattern<'static> {
+        match parsed {
+            Ok(pattern) => pattern,
+            Err(_) => panic!("invalid bundled RLE pattern"),
+        }
+    }
+}
+
+const fn is_whitespace(c: u8) -> bool {
+    matches!(c, b' ' | b'\t' | b'\r' | b'\n')
+}
+
+const fn is_alive_tag(c: u8) -> bool {
+    matches!(c, b'o' | b'A'..=b'X')
+}
+
+/// Skips spaces, tabs and line ends.
+const fn skip_whitespace(b: &[u8], mut i: usize) -> usize {
+    while i < b.len() && is_whitespace(b[i]) {
+        i += 1;
+    }
+    i
+}
+
+/// Skips spaces and tabs, staying on the same line.
+const fn skip_blanks(b: &[u8], mut i: usize) -> usize {
+    while i < b.len() && (b[i] == b' ' || b[i] == b'\t') {
+        i += 1;
+    }
+    i
+}
+
+/// Returns the index of the first byte of the next line.
+const fn next_line(b: &[u8], mut i: usize) -> usize {
+    while i < b.len() && b[i] != b'\n' {
+        i += 1;
+    }
+    i + 1
+}
+
+/// Expects `c`, surrounded by blanks, and returns the index after it.
+const fn expect(b: &[u8], i: usize, c: u8) -> Result<usize, ParsePatternError> {
+    let i = skip_blanks(b, i);
+    if i >= b.len() || b[i] != c {
+        return Err(ParsePatternError::InvalidHeader);
+    }
+    Ok(skip_blanks(b, i + 1))
+}
+
+/// Parses a decimal number, returning the index after it.
+const fn number(b: &[u8], mut i: usize) -> Option<(usize, usize)> {
+    let start = i;
+    let mut value: usize = 0;
+    while i < b.len() && b[i].is_ascii_digit() {
+        value = match value.checked_mul(10) {
+            Some(v) => match v.checked_add((b[i] - b'0') as usize) {
+                Some(v) => v,
+                None => return None,
+            },
+            None => return None,
+        };
+        i += 1;
+    }
+    if i == start {
+        None
+    } else {
+        Some((i, value))
+    }
+}
+
+/// Parses an optionally signed decimal number, returning the index after it.
+const fn signed_number(b: &[u8], i: usize) -> Option<(usize, isize)> {
+    let (negative, i) = if i < b.len() && b[i] == b'-' {
+        (true, i + 1)
+    } else if i < b.len() && b[i] == b'+' {
+        (false, i + 1)
+    } else {
+        (false, i)
+    };
+    match number(b, i) {
+        Some((i, value)) if value <= isize::MAX as usize => {
+            let value = value as isize;
+            Some((i, if negative { -value } else { value }))
+        }
+        _ => None,
+    }
+}
+
+/// Parses the next `x y` line of a Life 1.06 body, skipping `#` comment lines.
+///
+/// # Returns
+///
+/// The index after the line with the coordinates, or `None` at the end of the text.
+const fn next_coordinates(
+    b: &[u8],
+    i: usize,
+) -> Result<Option<(usize, isize, isize)>, ParsePatternError> {
+    let mut i = skip_whitespace(b, i);
+    while i < b.len() && b[i] == b'#' {
+        i = skip_whitespace(b, next_line(b, i));
+    }
+    if i >= b.len() {
+        return Ok(None);
+    }
+    let (i, x) = match signed_number(b, i) {
+        Some(value) => value,
+        None => return Err(ParsePatternError::InvalidCoordinates),
+    };
+    let (i, y) = match signed_number(b, skip_blanks(b, i)) {
+        Some(value) => value,
+        None => return Err(ParsePatternError::InvalidCoordinates),
+    };
+    let i = skip_blanks(b, i);
+    if i < b.len() && b[i] != b'\n' && b[i] != b'\r' {
+        return Err(ParsePatternError::InvalidCoordinates);
+    }
+    Ok(Some((i, x, y)))
+}
+
+/// Parses `<key> = <number>` and returns the index after it with the number.
+const fn header_value(b: &[u8], i: usize, key: u8) -> Result<(usize, usize), ParsePatternError> {
+    let i = skip_blanks(b, i);
+    if i >= b.len() || b[i] != key {
+        return Err(ParsePatternError::InvalidHeader);
+    }
+    let i = match expect(b, i + 1, b'=') {
+        Ok(i) => i,
+        Err(e) => return Err(e),
+    };
+    match number(b, i) {
+        Some((i, value)) => Ok((skip_blanks(b, i), value)),
+        None => Err(ParsePatternError::InvalidHeader),
+    }
+}
+
+/// Decodes the `<count><tag>` run starting at `i`.
+///
+/// # Returns
+///
+/// The index after the run, the run count and its tag. The end of the text is reported as a `!` tag.
+const fn next_run(b: &[u8], i: usize) -> Result<(usize, usize, u8), ParsePatternError> {
+    let i = skip_whitespace(b, i);
+    if i >= b.len() {
+        return Ok((i, 1, b'!'));
+    }
+    let (i, count) = match number(b, i) {
+        Some((i, count)) => (skip_whitespace(b, i), count),
+        None => (i, 1),
+    };
+    if i >= b.len() {
+        return Err(ParsePatternError::InvalidCharacter('!'));
+    }
+    match b[i] {
+        b'b' | b'.' | b'$' | b'!' => Ok((i + 1, count, b[i])),
+        c if is_alive_tag(c) => Ok((i + 1, count, c)),
+        c => Err(ParsePatternError::InvalidCharacter(c as char)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::classics::*;
+    use super::*;
+
+    fn cells(pattern: &Pattern) -> Vec<(usize, usize)> {
+        pattern.cells().collect()
+    }
+
+    #[test]
+    fn parses_header_and_body() {
+        assert_eq!(GLIDER.width(), 3);
+        assert_eq!(GLIDER.height(), 3);
+        assert_eq!(GLIDER.rule(), Some(Rule::CONWAY));
+        assert_eq!(cells(&GLIDER), [(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)]);
+    }
+
+    #[test]
+    fn header_rule_is_optional() {
+        let pattern = Pattern::parse("x=2,y=2\n2o$2o!").unwrap();
+        assert_eq!(pattern.rule(), None);
+        assert_eq!(cells(&pattern), [(0, 0), (1, 0), (0, 1), (1, 1)]);
+
+        let pattern = Pattern::parse("x = 1, y = 1, rule = B36/S23\r\no!").unwrap();
+        assert_eq!(pattern.rule(), Some(Rule::HIGHLIFE));
+    }
+
+    #[test]
+    fn decodes_multi_line_body_and_empty_rows() {
+        let pattern = Pattern::parse("x = 3, y = 4\no\nb$\n2$2bo!").unwrap();
+        assert_eq!(cells(&pattern), [(0, 0), (2, 3)]);
+    }
+
+    #[test]
+    fn rejects_malformed_patterns() {
+        assert_eq!(
+            Pattern::parse("bob$2bo$3o!"),
+            Err(ParsePatternError::MissingHeader)
+        );
+        assert_eq!(
+            Pattern::parse("x = 3 y = 3\n3o!"),
+            Err(ParsePatternError::InvalidHeader)
+        );
+        assert_eq!(
+            Pattern::parse("x = 3, y = 1, rule = 23/3\n3o!"),
+            Err(ParsePatternError::InvalidRule(
+                ParseRuleError::InvalidCharacter('2')
+            ))
+        );
+        assert_eq!(
+            Pattern::parse("x = 2, y = 1\n3o!"),
+            Err(ParsePatternError::TooWide)
+        );
+        assert_eq!(
+            Pattern::parse("x = 3, y = 1\n3o$o!"),
+            Err(ParsePatternError::TooHigh)
+        );
+        assert_eq!(
+            Pattern::parse("x = 3, y = 1\n3z!"),
+            Err(ParsePatternError::InvalidCharacter('z'))
+        );
+    }
+
+    #[test]
+    fn rejects_oversized_run_counts() {
+        let huge = usize::MAX;
+        let rows = format!("x = 3, y = 3\n{}$o$$3o!", huge);
+        assert_eq!(Pattern::parse(&rows), Err(ParsePatternError::TooHigh));
+        let rows = format!("x = 3, y = 3\n{}${}$o!", huge, huge);
+        assert_eq!(Pattern::parse(&rows), Err(ParsePatternError::TooHigh));
+        let cells = format!("x = 3, y = 1\n2b{}bo!", huge);
+        assert_eq!(Pattern::parse(&cells), Err(ParsePatternError::TooWide));
+    }
+
+    #[test]
+    fn classics_have_expected_populations() {
+        let populations: Vec<usize> = ALL.iter().map(|(_, p)| p.cells().count()).collect();
+        assert_eq!(populations, [5, 9, 48, 5, 7]);
+    }
+
+    #[test]
+    fn transforms_stay_in_the_transformed_box() {
+        for transform in Transform::ALL {
+            let (w, h) = transform.dimensions(ACORN.width(), ACORN.height());
+            for (x, y) in ACORN.cells() {
+                let (tx, ty) = transform.apply(x, y, ACORN.width(), ACORN.height());
+                assert!(tx < w && ty < h, "{:?}", transform);
+            }
+        }
+    }
+
+    #[test]
+    fn rotations_compose() {
+        let (w, h) = (ACORN.width(), ACORN.height());
+        for (x, y) in ACORN.cells() {
+            let (x1, y1) = Transform::Rotate90.apply(x, y, w, h);
+            let (x2, y2) = Transform::Rotate90.apply(x1, y1, h, w);
+            assert_eq!((x2, y2), Transform::Rotate180.apply(x, y, w, h));
+            let (x3, y3) = Transform::Rotate90.apply(x2, y2, w, h);
+            assert_eq!((x3, y3), Transform::Rotate270.apply(x, y, w, h));
+        }
+        // The top row becomes the right column.
+        assert_eq!(Transform::Rotate90.apply(0, 0, w, h), (h - 1, 0));
+    }
+
+    #[test]
+    fn parses_plaintext() {
+        let pattern = Pattern::parse_plaintext("!Name: Glider\n!\n.O\n..O\r\nOOO\n").unwrap();
+        assert_eq!((pattern.width(), pattern.height()), (3, 3));
+        assert_eq!(cells(&pattern), cells(&GLIDER));
+
+        let pattern = Pattern::parse_plaintext("O\n\n*").unwrap();
+        assert_eq!((pattern.width(), pattern.height()), (1, 3));
+        assert_eq!(cells(&pattern), [(0, 0), (0, 2)]);
+
+        assert_eq!(
+            Pattern::parse_plaintext(".O\n.x"),
+            Err(ParsePatternError::InvalidCharacter('x'))
+        );
+    }
+
+    #[test]
+    fn parses_life106() {
+        let pattern =
+            Pattern::parse_life106("#Life 1.06\n0 -1\n1 0\n-1 1\n#D comment\n0 1\n 1  1 \n")
+                .unwrap();
+        assert_eq!((pattern.width(), pattern.height()), (3, 3));
+        assert_eq!(pattern.offset(), (-1, -1));
+        let mut glider = cells(&pattern);
+        glider.sort_by_key(|&(x, y)| (y, x));
+        assert_eq!(glider, cells(&GLIDER));
+
+        let empty = Pattern::parse_life106("#Life 1.06\n").unwrap();
+        assert_eq!((empty.width(), empty.height()), (0, 0));
+        assert_eq!(empty.cells().count(), 0);
+
+        assert_eq!(
+            Pattern::parse_life106("0 0\n"),
+            Err(ParsePatternError::MissingHeader)
+        );
+        assert_eq!(
+            Pattern::parse_life106("#Life 1.06\n0 0 0\n"),
+            Err(ParsePatternError::InvalidCoordinates)
+        );
+        assert_eq!(
+            Pattern::parse_life106("#Life 1.06\n0\n"),
+            Err(ParsePatternError::InvalidCoordinates)
+        );
+    }
+}
@@ -0,0 +1,112 @@
+use cichlid::ColorRGB;
+
+/// An estimate of the current drawn by a ledstrip, to keep it within what the supply can deliver.
+///
+/// The current of a LED is its idle current plus, for each channel, its current at full intensity scaled by the
+/// level of the channel. WS2812s drive their channels with constant current sinks, so the estimate is linear.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct PowerModel {
+    /// The current drawn by the red channel of a LED at full intensity, in mA.
+    pub red_ma: u16,
+    /// The current drawn by the green channel of a LED at full intensity, in mA.
+    pub green_ma: u16,
+    /// The current drawn by the blue channel of a LED at full intensity, in mA.
+    pub blue_ma: u16,
+    /// The current drawn by a LED even when black, in µA as it is about a milliamp or less.
+    pub idle_ua: u16,
+    /// The highest current the supply can deliver to the ledstrip, in mA.
+    pub supply_ma: u32,
+}
+
+impl PowerModel {
+    /// Measured values of WS2812B LEDs, powered by a 500 mA USB port.
+    pub const WS2812B: PowerModel = PowerModel {
+        red_ma: 16,
+        green_ma: 11,
+        blue_ma: 15,
+        idle_ua: 1000,
+        supply_ma: 500,
+    };
+
+    /// Returns the current drawn by `leds` black LEDs, in mA.
+    pub const fn idle_ma(&self, leds: usize) -> u32 {
+        (leds as u64 * self.idle_ua as u64 / 1000) as u32
+    }
+
+    /// Returns the current left to light the LEDs once the idle current of `leds` LEDs is drawn, in mA.
+    pub const fn budget_ma(&self, leds: usize) -> u32 {
+        self.supply_ma.saturating_sub(self.idle_ma(leds))
+    }
+
+    /// Estimates the current drawn by a ledstrip showing `colors`, in mA.
+    pub fn estimate(&self, colors: &[ColorRGB]) -> u32 {
+        let mut levels = [0; 3];
+        for color in colors {
+            levels[0] += color.r as u64;
+            levels[1] += color.g as u64;
+            levels[2] += color.b as u64;
+        }
+        self.idle_ma(colors.len()) + (self.weighted(levels) / u8::MAX as u64) as u32
+    }
+
+    /// Weighs the sums of the levels of each channel by their current.
+    ///
+    /// # Returns
+    ///
+    /// The current drawn by the channels, in mA multiplied by the full intensity level.
+    pub const fn weighted(&self, levels: [u64; 3]) -> u64 {
+        levels[0] * self.red_ma as u64
+            + levels[1] * self.green_ma as u64
+            + levels[2] * self.blue_ma as u64
+    }
+}
+
+impl Default for PowerModel {
+    fn default() -> Self {
+        Self::WS2812B
+    }
+}
+
+/// The current of a frame sent by an `OutputStage` with a power limit.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct PowerEstimate {
+    /// The current the frame would have drawn without the limiter, in mA.
+    pub requested_ma: u32,
+    /// The current drawn by the frame as sent, in mA.
+    pub drawn_ma: u32,
+}
+
+impl PowerEstimate {
+    /// Returns `true` if the frame was dimmed to stay within the supply limit.
+    pub fn is_limited(&self) -> bool {
+        self.drawn_ma < self.requested_ma
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn black_leds_draw_their_idle_current() {
+        let model = PowerModel::WS2812B;
+        assert_eq!(model.estimate(&[ColorRGB::Black; 144]), 144);
+        assert_eq!(model.idle_ma(144), 144);
+        assert_eq!(model.budget_ma(144), 356);
+        assert_eq!(model.budget_ma(1000), 0);
+    }
+
+    #[test]
+    fn channels_add_up() {
+        let model = PowerModel::WS2812B;
+        assert_eq!(model.estimate(&[ColorRGB::White]), 1 + 16 + 11 + 15);
+        let colors = [ColorRGB {
+            r: 255,
+            g: 0,
+            b: 128,
+        }; 10];
+        assert_eq!(model.estimate(&colors), 10 + 160 + 75);
+    }
+}
@@ -0,0 +1,95 @@
+use rand_core::{impls, Error, RngCore, SeedableRng};
+
+/// Marsaglia's 32-bit xorshift generator.
+///
+/// It is tiny, fast on the Cortex-M0+ (only 32-bit shifts and xors) and fully determined by its seed,
+/// so a board randomized from a logged seed can be replayed later. It is not suitable for cryptography.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct XorShift32 {
+    state: u32,
+}
+
+impl XorShift32 {
+    /// The state used in place of a zero seed, which would only ever produce zeros.
+    const ZERO_SEED_REPLACEMENT: u32 = 0x9E37_79B9;
+
+    /// Creates a new generator from a 32-bit seed.
+    ///
+    /// # Arguments
+    ///
+    /// * `seed`: Any value, `0` is replaced by a fixed non-zero state.
+    pub const fn new(seed: u32) -> Self {
+        XorShift32 {
+            state: if seed == 0 {
+                Self::ZERO_SEED_REPLACEMENT
+            } else {
+                seed
+            },
+        }
+    }
+}
+
+impl RngCore for XorShift32 {
+    fn next_u32(&mut self) -> u32 {
+        let mut x = self.state;
+        x ^= x << 13;
+        x ^= x >> 17;
+        x ^= x << 5;
+        self.state = x;
+        x
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        impls::next_u64_via_u32(self)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        impls::fill_bytes_via_next(self, dest)
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+impl SeedableRng for XorShift32 {
+    type Seed = [u8; 4];
+
+    fn from_seed(seed: Self::Seed) -> Self {
+        Self::new(u32::from_le_bytes(seed))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game_grid::GameGrid;
+
+    #[test]
+    fn matches_reference_sequence() {
+        let mut rng = XorShift32::new(1);
+        assert_eq!(rng.next_u32(), 270_369);
+        assert_eq!(rng.next_u32(), 67_634_689);
+        assert_eq!(rng.next_u32(), 2_647_435_461);
+    }
+
+    #[test]
+    fn zero_seed_does_not_get_stuck() {
+        let mut rng = XorShift32::new(0);
+        assert_ne!(rng.next_u32(), 0);
+        assert_eq!(XorShift32::from_seed([0; 4]), XorShift32::new(0));
+    }
+
+    #[test]
+    fn same_seed_gives_same_board() {
+        let mut a = GameGrid::<17, 8>::default();
+        let mut b = GameGrid::<17, 8>::default();
+        a.randomize_with(&mut XorShift32::new(0xC0FFEE), 0.42);
+        b.randomize_with(&mut XorShift32::new(0xC0FFEE), 0.42);
+        assert_eq!(a.get_hash(), b.get_hash());
+
+        b.randomize_with(&mut XorShift32::new(0xBEEF), 0.42);
+        assert_ne!(a.get_hash(), b.get_hash());
+    }
+}
@@ -0,0 +1,258 @@
+use core::fmt;
+use core::str::FromStr;
+
+/// A Life-like cellular automaton rule, in B/S notation.
+///
+/// Each rule is made of two sets of neighbor counts (0 to 8):
+/// * `birth`: a dead cell with this number of alive neighbors becomes alive.
+/// * `survival`: an alive cell with this number of alive neighbors stays alive.
+///
+/// The sets are stored as bitmasks, bit `n` being set when `n` neighbors is part of the set.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Rule {
+    birth: u16,
+    survival: u16,
+}
+
+/// Errors returned while parsing a rulestring.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ParseRuleError {
+    /// The rulestring has no `B` section.
+    MissingBirth,
+    /// The rulestring has no `S` section.
+    MissingSurvival,
+    /// A section appears more than once.
+    DuplicatedSection,
+    /// The sections are not separated by a `/`.
+    MissingSeparator,
+    /// A character is neither a section letter nor a neighbor count between 0 and 8.
+    InvalidCharacter(char),
+}
+
+impl Rule {
+    /// Conway's Game of Life: `B3/S23`.
+    pub const CONWAY: Rule = Rule::new(1 << 3, 1 << 2 | 1 << 3);
+
+    /// HighLife: `B36/S23`, Conway's rule plus a replicator.
+    pub const HIGHLIFE: Rule = Rule::new(1 << 3 | 1 << 6, 1 << 2 | 1 << 3);
+
+    /// Day & Night: `B3678/S34678`, symmetric between alive and dead cells.
+    pub const DAY_AND_NIGHT: Rule = Rule::new(
+        1 << 3 | 1 << 6 | 1 << 7 | 1 << 8,
+        1 << 3 | 1 << 4 | 1 << 6 | 1 << 7 | 1 << 8,
+    );
+
+    /// Seeds: `B2/S`, every alive cell dies at each generation.
+    pub const SEEDS: Rule = Rule::new(1 << 2, 0);
+
+    /// Creates a new rule from its birth and survival bitmasks.
+    ///
+    /// # Arguments
+    ///
+    /// * `birth`: Bit `n` is set if a dead cell with `n` alive neighbors becomes alive.
+    /// * `survival`: Bit `n` is set if an alive cell with `n` alive neighbors stays alive.
+    pub const fn new(birth: u16, survival: u16) -> Self {
+        Rule {
+            birth: birth & 0x1FF,
+            survival: survival & 0x1FF,
+        }
+    }
+
+    /// Parses a rulestring such as `"B36/S23"`.
+    ///
+    /// Sections may come in any order and letters are case-insensitive, so `"s23/b3"` is also accepted.
+    /// Being a `const fn`, it can be used to validate a rule at compile time.
+    ///
+    /// # Returns
+    ///
+    /// The parsed rule, or the reason why the rulestring is invalid.
+    pub const fn parse(rulestring: &str) -> Result<Rule, ParseRuleError> {
+        let bytes = rulestring.as_bytes();
+        Rule::parse_bytes(bytes, 0, bytes.len())
+    }
+
+    /// Parses the rulestring held by `bytes[start..end]`, see `parse`.
+    pub(crate) const fn parse_bytes(
+        bytes: &[u8],
+        start: usize,
+        end: usize,
+    ) -> Result<Rule, ParseRuleError> {
+        let mut birth: Option<u16> = None;
+        let mut survival: Option<u16> = None;
+        // 0: no section yet, 1: birth, 2: survival
+        let mut section = 0;
+        let mut expect_section = true;
+        let mut i = start;
+        while i < end {
+            let c = bytes[i];
+            match c {
+                b'B' | b'b' | b'S' | b's' => {
+                    if !expect_section {
+                        return Err(ParseRuleError::MissingSeparator);
+                    }
+                    expect_section = false;
+                    if c == b'B' || c == b'b' {
+                        if birth.is_some() {
+                            return Err(ParseRuleError::DuplicatedSection);
+                        }
+                        birth = Some(0);
+                        section = 1;
+                    } else {
+                        if survival.is_some() {
+                            return Err(ParseRuleError::DuplicatedSection);
+                        }
+                        survival = Some(0);
+                        section = 2;
+                    }
+                }
+                b'/' => {
+                    if expect_section {
+                        return Err(ParseRuleError::InvalidCharacter('/'));
+                    }
+                    expect_section = true;
+                }
+                b'0'..=b'8' if !expect_section => {
+                    let bit = 1 << (c - b'0');
+                    if section == 1 {
+                        birth = match birth {
+                            Some(mask) => Some(mask | bit),
+                            None => None,
+                        };
+                    } else {
+                        survival = match survival {
+                            Some(mask) => Some(mask | bit),
+                            None => None,
+                        };
+                    }
+                }
+                _ => return Err(ParseRuleError::InvalidCharacter(c as char)),
+            }
+            i += 1;
+        }
+        match (birth, survival) {
+            (None, _) => Err(ParseRuleError::MissingBirth),
+            (_, None) => Err(ParseRuleError::MissingSurvival),
+            (Some(birth), Some(survival)) => Ok(Rule::new(birth, survival)),
+        }
+    }
+
+    /// Computes the next state of a cell.
+    ///
+    /// # Arguments
+    ///
+    /// * `alive`: The current state of the cell.
+    /// * `neighbors`: The number of alive neighbors of the cell.
+    ///
+    /// # Returns
+    ///
+    /// Returns `true` if the cell is alive at the next generation.
+    pub const fn next_state(&self, alive: bool, neighbors: u8) -> bool {
+        let mask = if alive { self.survival } else { self.birth };
+        neighbors <= 8 && mask & (1 << neighbors) != 0
+    }
+}
+
+impl Default for Rule {
+    /// Creates Conway's Game of Life rule.
+    fn default() -> Self {
+        Rule::CONWAY
+    }
+}
+
+impl FromStr for Rule {
+    type Err = ParseRuleError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Rule::parse(s)
+    }
+}
+
+impl fmt::Display for Rule {
+    /// Writes the rule in canonical `B.../S...` notation.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("B")?;
+        for n in 0..=8 {
+            if self.birth & (1 << n) != 0 {
+                write!(f, "{}", n)?;
+            }
+        }
+        f.write_str("/S")?;
+        for n in 0..=8 {
+            if self.survival & (1 << n) != 0 {
+                write!(f, "{}", n)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for ParseRuleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseRuleError::MissingBirth => f.write_str("missing birth (B) section"),
+            ParseRuleError::MissingSurvival => f.write_str("missing survival (S) section"),
+            ParseRuleError::DuplicatedSection => f.write_str("duplicated section"),
+            ParseRuleError::MissingSeparator => f.write_str("sections must be separated by '/'"),
+            ParseRuleError::InvalidCharacter(c) => write!(f, "invalid character '{}'", c),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_well_known_rules() {
+        assert_eq!(Rule::parse("B3/S23"), Ok(Rule::CONWAY));
+        assert_eq!(Rule::parse("B36/S23"), Ok(Rule::HIGHLIFE));
+        assert_eq!(Rule::parse("B3678/S34678"), Ok(Rule::DAY_AND_NIGHT));
+        assert_eq!(Rule::parse("B2/S"), Ok(Rule::SEEDS));
+    }
+
+    #[test]
+    fn accepts_any_section_order_and_case() {
+        assert_eq!("S23/B3".parse(), Ok(Rule::CONWAY));
+        assert_eq!("b36/s23".parse(), Ok(Rule::HIGHLIFE));
+    }
+
+    #[test]
+    fn rejects_malformed_rulestrings() {
+        assert_eq!(Rule::parse("S23"), Err(ParseRuleError::MissingBirth));
+        assert_eq!(Rule::parse("B3"), Err(ParseRuleError::MissingSurvival));
+        assert_eq!(
+            Rule::parse("B3/S23/B6"),
+            Err(ParseRuleError::DuplicatedSection)
+        );
+        assert_eq!(Rule::parse("B3S23"), Err(ParseRuleError::MissingSeparator));
+        assert_eq!(
+            Rule::parse("B39/S23"),
+            Err(ParseRuleError::InvalidCharacter('9'))
+        );
+        assert_eq!(
+            Rule::parse("23/3"),
+            Err(ParseRuleError::InvalidCharacter('2'))
+        );
+    }
+
+    #[test]
+    fn displays_canonical_rulestring() {
+        assert_eq!(Rule::CONWAY.to_string(), "B3/S23");
+        assert_eq!(
+            Rule::parse("s8764/b3876").unwrap().to_string(),
+            "B3678/S4678"
+        );
+        assert_eq!(Rule::SEEDS.to_string(), "B2/S");
+    }
+
+    #[test]
+    fn next_state_follows_birth_and_survival_sets() {
+        let rule = Rule::HIGHLIFE;
+        assert!(rule.next_state(false, 3));
+        assert!(rule.next_state(false, 6));
+        assert!(!rule.next_state(false, 2));
+        assert!(rule.next_state(true, 2));
+        assert!(!rule.next_state(true, 6));
+        assert!(!Rule::SEEDS.next_state(true, 2));
+    }
+}
@@ -0,0 +1,278 @@
+/// A fixed-capacity ring buffer holding the hashes of the last `N` generations.
+pub struct HashHistory<const N: usize> {
+    hashes: [u64; N],
+    /// Number of valid hashes, up to `N`.
+    len: usize,
+    /// Index where the next hash will be written.
+    next: usize,
+}
+
+impl<const N: usize> HashHistory<N> {
+    /// Creates an empty history.
+    pub const fn new() -> Self {
+        HashHistory {
+            hashes: [0; N],
+            len: 0,
+            next: 0,
+        }
+    }
+
+    /// Records the hash of a new generation, dropping the oldest one if the history is full.
+    pub fn push(&mut self, hash: u64) {
+        if N == 0 {
+            return;
+        }
+        self.hashes[self.next] = hash;
+        self.next = (self.next + 1) % N;
+        self.len = (self.len + 1).min(N);
+    }
+
+    /// Returns the hash recorded `age` generations ago, `0` being the most recent one.
+    pub fn get(&self, age: usize) -> Option<u64> {
+        if age >= self.len {
+            return None;
+        }
+        Some(self.hashes[(self.next + N - 1 - age) % N])
+    }
+
+    /// Returns the number of recorded hashes.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if no hash has been recorded.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Forgets every recorded hash.
+    pub fn clear(&mut self) {
+        self.len = 0;
+        self.next = 0;
+    }
+}
+
+impl<const N: usize> Default for HashHistory<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// What the recent generations of a board look like.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Stagnation {
+    /// The board is still evolving, or repeats with a period longer than the history.
+    Live,
+    /// The board did not change since the previous generation.
+    StillLife,
+    /// The board is back to the state it had `period` generations ago.
+    Oscillator {
+        /// Number of generations of one cycle.
+        period: usize,
+    },
+}
+
+/// When a stagnating board should be randomized again.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ReseedPolicy {
+    /// Number of generations a still life is kept before reseeding.
+    pub still_life_repeats: usize,
+    /// Number of full cycles an oscillator is kept before reseeding.
+    pub oscillator_repeats: usize,
+}
+
+impl Default for ReseedPolicy {
+    /// Reseeds still lifes right away and oscillators after 3 cycles.
+    fn default() -> Self {
+        ReseedPolicy {
+            still_life_repeats: 1,
+            oscillator_repeats: 3,
+        }
+    }
+}
+
+/// Detects still lifes and oscillators of period up to `N` from the hashes of successive generations.
+pub struct StagnationDetector<const N: usize> {
+    history: HashHistory<N>,
+    policy: ReseedPolicy,
+    verdict: Stagnation,
+    /// Number of consecutive generations with the same verdict.
+    streak: usize,
+}
+
+impl<const N: usize> StagnationDetector<N> {
+    /// Creates a new detector.
+    ///
+    /// # Arguments
+    ///
+    /// * `policy`: When `should_reseed` reports a stagnating board.
+    pub const fn new(policy: ReseedPolicy) -> Self {
+        StagnationDetector {
+            history: HashHistory::new(),
+            policy,
+            verdict: Stagnation::Live,
+            streak: 0,
+        }
+    }
+
+    /// Records the hash of a new generation and classifies the board.
+    ///
+    /// # Arguments
+    ///
+    /// * `hash`: The hash of the new generation, see `GameGrid::get_hash`.
+    ///
+    /// # Returns
+    ///
+    /// The smallest period the board repeats with, if any.
+    pub fn observe(&mut self, hash: u64) -> Stagnation {
+        let period = (0..self.history.len())
+            .find(|&age| self.history.get(age) == Some(hash))
+            .map(|age| age + 1);
+        self.history.push(hash);
+
+        let verdict = match period {
+            None => Stagnation::Live,
+            Some(1) => Stagnation::StillLife,
+            Some(period) => Stagnation::Oscillator { period },
+        };
+        self.streak = match verdict {
+            Stagnation::Live => 0,
+            _ if verdict == self.verdict => self.streak + 1,
+            _ => 1,
+        };
+        self.verdict = verdict;
+        verdict
+    }
+
+    /// Returns the last verdict of `observe`.
+    pub fn verdict(&self) -> Stagnation {
+        self.verdict
+    }
+
+    /// Returns how many times the current period repeated: generations for a still life, cycles for an oscillator.
+    pub fn repeats(&self) -> usize {
+        match self.verdict {
+            Stagnation::Live => 0,
+            Stagnation::StillLife => self.streak,
+            Stagnation::Oscillator { period } => self.streak / period,
+        }
+    }
+
+    /// Returns `true` if the board stagnated long enough, according to the reseed policy.
+    pub fn should_reseed(&self) -> bool {
+        match self.verdict {
+            Stagnation::Live => false,
+            Stagnation::StillLife => self.repeats() >= self.policy.still_life_repeats,
+            Stagnation::Oscillator { .. } => self.repeats() >= self.policy.oscillator_repeats,
+        }
+    }
+
+    /// Forgets the history, to be called once the board has been reseeded.
+    pub fn reset(&mut self) {
+        self.history.clear();
+        self.verdict = Stagnation::Live;
+        self.streak = 0;
+    }
+}
+
+impl<const N: usize> Default for StagnationDetector<N> {
+    fn default() -> Self {
+        Self::new(ReseedPolicy::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game_grid::GameGrid;
+
+    #[test]
+    fn history_returns_most_recent_first_and_wraps() {
+        let mut history = HashHistory::<3>::new();
+        assert!(history.is_empty());
+        for hash in 1..=5 {
+            history.push(hash);
+        }
+        assert_eq!(history.len(), 3);
+        assert_eq!(history.get(0), Some(5));
+        assert_eq!(history.get(2), Some(3));
+        assert_eq!(history.get(3), None);
+
+        history.clear();
+        assert_eq!(history.get(0), None);
+    }
+
+    #[test]
+    fn detects_still_life() {
+        let mut gg = GameGrid::<8, 8>::default();
+        for (x, y) in [(2, 2), (3, 2), (2, 3), (3, 3)] {
+            gg.set_alive(x, y, true);
+        }
+        let mut detector = StagnationDetector::<8>::default();
+        detector.observe(gg.get_hash());
+        gg.update();
+        assert_eq!(detector.observe(gg.get_hash()), Stagnation::StillLife);
+        assert!(detector.should_reseed());
+    }
+
+    #[test]
+    fn detects_blinker_period() {
+        let mut gg = GameGrid::<8, 8>::default();
+        for (x, y) in [(2, 3), (3, 3), (4, 3)] {
+            gg.set_alive(x, y, true);
+        }
+        let mut detector = StagnationDetector::<8>::default();
+        assert_eq!(detector.observe(gg.get_hash()), Stagnation::Live);
+        gg.update();
+        assert_eq!(detector.observe(gg.get_hash()), Stagnation::Live);
+        gg.update();
+        assert_eq!(
+            detector.observe(gg.get_hash()),
+            Stagnation::Oscillator { period: 2 }
+        );
+    }
+
+    #[test]
+    fn periods_longer_than_history_are_live() {
+        let mut detector = StagnationDetector::<3>::default();
+        for hash in [1, 2, 3, 4, 1, 2, 3, 4] {
+            assert_eq!(detector.observe(hash), Stagnation::Live);
+        }
+    }
+
+    #[test]
+    fn reseeds_after_configured_repeats() {
+        let mut detector = StagnationDetector::<8>::new(ReseedPolicy {
+            still_life_repeats: 2,
+            oscillator_repeats: 2,
+        });
+        for hash in [1, 2, 3, 1, 2, 3, 1, 2] {
+            detector.observe(hash);
+            assert!(!detector.should_reseed());
+        }
+        detector.observe(3);
+        assert_eq!(detector.verdict(), Stagnation::Oscillator { period: 3 });
+        assert_eq!(detector.repeats(), 2);
+        assert!(detector.should_reseed());
+
+        detector.reset();
+        assert_eq!(detector.observe(7), Stagnation::Live);
+        assert_eq!(detector.observe(7), Stagnation::StillLife);
+        assert!(!detector.should_reseed());
+        detector.observe(7);
+        assert!(detector.should_reseed());
+    }
+
+    #[test]
+    fn changing_period_restarts_the_count() {
+        let mut detector = StagnationDetector::<8>::default();
+        for hash in [1, 2, 1, 2] {
+            detector.observe(hash);
+        }
+        assert_eq!(detector.repeats(), 1);
+        detector.observe(2);
+        assert_eq!(detector.verdict(), Stagnation::StillLife);
+        assert_eq!(detector.repeats(), 1);
+    }
+}
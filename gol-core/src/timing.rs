@@ -0,0 +1,141 @@
+/// How long the LED transitions last, relative to the interval between two generations.
+///
+/// Tying the transitions to the interval keeps them whole whatever the speed of the game: they neither get cut off
+/// by the next generation nor leave the board frozen for most of a slow one.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct TransitionTiming {
+    /// The part of the generation interval a transition lasts, in percent, at most `100`.
+    percent: u8,
+}
+
+impl TransitionTiming {
+    /// Transitions last the whole generation interval, the board never stays still.
+    pub const CONTINUOUS: TransitionTiming = TransitionTiming::percent(100);
+
+    /// Creates the timing of transitions lasting `percent` percent of the generation interval.
+    ///
+    /// # Panics
+    ///
+    /// If `percent` is above `100`, transitions would still run when the next generation comes.
+    pub const fn percent(percent: u8) -> Self {
+        assert!(
+            percent <= 100,
+            "a transition cannot last longer than a generation"
+        );
+        TransitionTiming { percent }
+    }
+
+    /// Returns the duration of a transition, in the unit of `interval`.
+    pub const fn duration(&self, interval: u64) -> u64 {
+        interval * self.percent as u64 / 100
+    }
+}
+
+impl Default for TransitionTiming {
+    /// Transitions last a fifth of the generation interval, one second with the default interval of five.
+    fn default() -> Self {
+        Self::percent(20)
+    }
+}
+
+/// The clock of the current transition, which turns the time of a frame into the progress of the transition.
+///
+/// Times are plain numbers, e.g. the microseconds of an `embassy_time::Instant`, so the progress can be computed
+/// for any frame time, whatever the frame rate.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TransitionClock {
+    timing: TransitionTiming,
+    start: u64,
+    duration: u64,
+}
+
+impl TransitionClock {
+    /// Creates a clock whose transition is already done.
+    pub const fn new(timing: TransitionTiming) -> Self {
+        TransitionClock {
+            timing,
+            start: 0,
+            duration: 0,
+        }
+    }
+
+    /// Starts the transition of a new generation.
+    ///
+    /// # Arguments
+    ///
+    /// * `now`: The current time.
+    /// * `interval`: The interval between two generations, in the same unit as `now`.
+    pub fn start(&mut self, now: u64, interval: u64) {
+        self.start = now;
+        self.duration = self.timing.duration(interval);
+    }
+
+    /// Returns the duration of the current transition.
+    pub fn duration(&self) -> u64 {
+        self.duration
+    }
+
+    /// Returns the progress of the transition at `now`, from `0` at its start to `255` once done.
+    pub fn progress(&self, now: u64) -> u8 {
+        let elapsed = now.saturating_sub(self.start);
+        if elapsed >= self.duration {
+            return u8::MAX;
+        }
+        (elapsed * u8::MAX as u64 / self.duration) as u8
+    }
+
+    /// Returns `true` once the transition is over at `now`.
+    pub fn is_done(&self, now: u64) -> bool {
+        now.saturating_sub(self.start) >= self.duration
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn duration_is_a_fraction_of_the_interval() {
+        assert_eq!(TransitionTiming::default().duration(5_000_000), 1_000_000);
+        assert_eq!(TransitionTiming::CONTINUOUS.duration(300), 300);
+        assert_eq!(TransitionTiming::percent(0).duration(300), 0);
+    }
+
+    #[test]
+    fn progress_follows_the_elapsed_time() {
+        let mut clock = TransitionClock::new(TransitionTiming::percent(50));
+        assert!(clock.is_done(0));
+        assert_eq!(clock.progress(0), u8::MAX);
+
+        clock.start(1000, 2000);
+        assert_eq!(clock.duration(), 1000);
+        assert_eq!(clock.progress(1000), 0);
+        assert_eq!(clock.progress(1500), 127);
+        assert!(!clock.is_done(1999));
+        assert_eq!(clock.progress(2000), u8::MAX);
+        assert!(clock.is_done(2000));
+        assert_eq!(clock.progress(900), 0);
+    }
+
+    #[test]
+    fn progress_does_not_depend_on_the_frame_rate() {
+        let mut clock = TransitionClock::new(TransitionTiming::default());
+        clock.start(0, 250_000);
+        let sampled = |frame: u64| {
+            (0..=50_000 / frame)
+                .map(|i| clock.progress(i * frame))
+                .collect::<Vec<_>>()
+        };
+        let (fast, slow) = (sampled(1_000), sampled(10_000));
+        assert_eq!(fast.first(), slow.first());
+        assert_eq!(fast.last(), slow.last());
+        assert!(slow.iter().all(|p| fast.contains(p)));
+    }
+
+    #[test]
+    #[should_panic]
+    fn transitions_cannot_overlap_generations() {
+        TransitionTiming::percent(101);
+    }
+}
@@ -0,0 +1,109 @@
+/// The way the edges of the game grid are glued together when counting neighbors.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Topology {
+    /// Everything outside the grid is dead.
+    #[default]
+    Bounded,
+    /// Both the left/right and top/bottom edges wrap around.
+    Torus,
+    /// The left/right edges wrap around, the top/bottom edges wrap around mirrored left to right.
+    KleinBottle,
+    /// Only the left/right edges wrap around, everything above or below the grid is dead.
+    Cylinder,
+    /// The edges act as mirrors: the cells just outside the grid are copies of the edge cells.
+    Mirror,
+}
+
+impl Topology {
+    /// Finds the cell at offset `(dx, dy)` from the cell at `(x, y)` position.
+    ///
+    /// # Arguments
+    ///
+    /// * `x`, `y`: The position of the cell, inside a `width` x `height` grid.
+    /// * `dx`, `dy`: The offset of the neighbor, between -1 and 1.
+    /// * `width`, `height`: The dimensions of the grid.
+    ///
+    /// # Returns
+    ///
+    /// The position of the neighbor inside the grid, or `None` if it is outside of it (always dead).
+    pub fn neighbor(
+        self,
+        x: usize,
+        y: usize,
+        dx: isize,
+        dy: isize,
+        width: usize,
+        height: usize,
+    ) -> Option<(usize, usize)> {
+        let (w, h) = (width as isize, height as isize);
+        let nx = x as isize + dx;
+        let ny = y as isize + dy;
+        let x_outside = nx < 0 || nx >= w;
+        let y_outside = ny < 0 || ny >= h;
+
+        let (nx, ny) = match self {
+            Topology::Bounded if x_outside || y_outside => return None,
+            Topology::Bounded => (nx, ny),
+            Topology::Torus => (nx.rem_euclid(w), ny.rem_euclid(h)),
+            Topology::KleinBottle => {
+                // Crossing the top/bottom edge flips the grid left to right.
+                let nx = if y_outside { w - 1 - nx } else { nx };
+                (nx.rem_euclid(w), ny.rem_euclid(h))
+            }
+            Topology::Cylinder if y_outside => return None,
+            Topology::Cylinder => (nx.rem_euclid(w), ny),
+            Topology::Mirror => (reflect(nx, w), reflect(ny, h)),
+        };
+        Some((nx as usize, ny as usize))
+    }
+}
+
+/// Reflects a coordinate lying at most one cell outside of `0..len` back inside.
+fn reflect(v: isize, len: isize) -> isize {
+    if v < 0 {
+        -1 - v
+    } else if v >= len {
+        2 * len - 1 - v
+    } else {
+        v
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn inner_neighbors_do_not_depend_on_topology() {
+        for topology in [
+            Topology::Bounded,
+            Topology::Torus,
+            Topology::KleinBottle,
+            Topology::Cylinder,
+            Topology::Mirror,
+        ] {
+            assert_eq!(topology.neighbor(3, 3, -1, 1, 17, 8), Some((2, 4)));
+        }
+    }
+
+    #[test]
+    fn corner_neighbors_follow_the_topology() {
+        assert_eq!(Topology::Bounded.neighbor(0, 0, -1, -1, 17, 8), None);
+        assert_eq!(Topology::Torus.neighbor(0, 0, -1, -1, 17, 8), Some((16, 7)));
+        assert_eq!(
+            Topology::KleinBottle.neighbor(0, 0, -1, -1, 17, 8),
+            Some((0, 7))
+        );
+        assert_eq!(
+            Topology::KleinBottle.neighbor(3, 7, 1, 1, 17, 8),
+            Some((12, 0))
+        );
+        assert_eq!(Topology::Cylinder.neighbor(0, 0, -1, -1, 17, 8), None);
+        assert_eq!(
+            Topology::Cylinder.neighbor(0, 3, -1, -1, 17, 8),
+            Some((16, 2))
+        );
+        assert_eq!(Topology::Mirror.neighbor(0, 0, -1, -1, 17, 8), Some((0, 0)));
+        assert_eq!(Topology::Mirror.neighbor(16, 7, 1, 0, 17, 8), Some((16, 7)));
+    }
+}
@@ -0,0 +1,164 @@
+//! Headless recorder: runs the simulation from a seed and writes the ledstrip frames as an animated GIF or APNG.
+//!
+//! Usage: `cargo record-host -- --output <file.gif|file.png> [--seed <u32>] [--rule <B/S rulestring>]
+//! [--interval <ms>] [--generations <n>] [--fps <n>] [--pitch <px>]`
+
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::PathBuf;
+
+use gol_core::config::FRAME_INTERVAL_US;
+use gol_sim::cli::RunOptions;
+use gol_sim::recorder::{DotRenderer, Format, Recorder, Recording};
+use gol_sim::simulation::Simulation;
+
+const USAGE: &str =
+    "usage: gol-record --output <file.gif|file.png> [--seed <u32>] [--rule <B/S rulestring>] \
+                     [--interval <ms>] [--generations <n>] [--fps <n>] [--pitch <px>]\n\
+     --fps must divide 50, the frame rate of the firmware";
+
+/// The frame rate of the firmware, in frames per second.
+const FIRMWARE_FPS: u64 = 1_000_000 / FRAME_INTERVAL_US;
+
+/// The largest distance between two LEDs in the images, in pixels, which keeps them in the size limits of GIF.
+const MAX_PITCH: usize = 256;
+
+const _: () = assert!(DotRenderer::new(MAX_PITCH).width() <= Format::Gif.max_size());
+
+/// The options of a recording, from the command line.
+struct Options {
+    output: PathBuf,
+    format: Format,
+    run: RunOptions,
+    generations: u64,
+    /// The length of the recording, in µs.
+    duration: u64,
+    /// The number of images per second of the recording, a divisor of the frame rate of the firmware so every image
+    /// stands for the same number of ledstrip frames.
+    fps: u64,
+    /// The distance between two LEDs in the images, in pixels.
+    pitch: usize,
+}
+
+impl Options {
+    fn parse(mut args: impl Iterator<Item = String>) -> Result<Options, String> {
+        let mut output = None;
+        let mut options = Options {
+            output: PathBuf::new(),
+            format: Format::Gif,
+            run: RunOptions::new(1),
+            generations: 10,
+            duration: 0,
+            fps: 25,
+            pitch: 16,
+        };
+        while let Some(arg) = args.next() {
+            let value = args
+                .next()
+                .ok_or_else(|| format!("missing value after {}", arg))?;
+            let number = |value: &str| {
+                value
+                    .parse::<u64>()
+                    .map_err(|e| format!("invalid {} {}: {}", arg, value, e))
+            };
+            match arg.as_str() {
+                "--output" => output = Some(PathBuf::from(value)),
+                "--generations" => options.generations = number(&value)?,
+                "--fps" => options.fps = number(&value)?,
+                "--pitch" => options.pitch = number(&value)?.max(1) as usize,
+                _ if options.run.parse_option(&arg, &value)? => {}
+                _ => return Err(format!("unknown option {}", arg)),
+            }
+        }
+        options.output = output.ok_or("missing --output")?;
+        options.format = Format::from_path(&options.output)
+            .ok_or_else(|| format!("{} is neither a .gif nor a .png", options.output.display()))?;
+        if options.generations == 0 {
+            return Err("--generations must be at least 1".into());
+        }
+        options.duration = options
+            .generations
+            .checked_mul(options.run.interval)
+            .ok_or("--generations times --interval is too long a recording")?;
+        if FIRMWARE_FPS.checked_rem(options.fps) != Some(0) {
+            return Err(format!("--fps must divide {}", FIRMWARE_FPS));
+        }
+        if options.pitch > MAX_PITCH {
+            return Err(format!("--pitch must be at most {} pixels", MAX_PITCH));
+        }
+        Ok(options)
+    }
+
+    /// Returns the number of ledstrip frames each image of the recording stands for.
+    fn frames_per_image(&self) -> u64 {
+        FIRMWARE_FPS / self.fps
+    }
+
+    /// Returns the number of images of the recording, one every `frames_per_image` ledstrip frames.
+    fn images(&self) -> u64 {
+        let image_interval = self.frames_per_image() * FRAME_INTERVAL_US;
+        self.duration / image_interval + (self.duration % image_interval).min(1)
+    }
+}
+
+fn main() {
+    let options = match Options::parse(std::env::args().skip(1)) {
+        Ok(options) => options,
+        Err(error) => {
+            eprintln!("{}\n{}", error, USAGE);
+            std::process::exit(2);
+        }
+    };
+    let renderer = DotRenderer::new(options.pitch);
+    let recording = Recording {
+        width: renderer.width(),
+        height: renderer.height(),
+        frame_interval: options.frames_per_image() * FRAME_INTERVAL_US,
+        frames: options.images(),
+    };
+    let result = File::create(&options.output)
+        .and_then(|file| recording.start(BufWriter::new(file), options.format))
+        .and_then(|mut recorder| {
+            record(&options, &renderer, &mut recorder)?;
+            recorder.finish()
+        });
+    if let Err(error) = result {
+        eprintln!("gol-record: {}: {}", options.output.display(), error);
+        std::process::exit(1);
+    }
+    println!(
+        "{}: {} frames, {} generations of rule {} from seed {:#x}",
+        options.output.display(),
+        recording.frames,
+        options.generations,
+        options.run.rule,
+        options.run.seed
+    );
+}
+
+/// Runs the simulation on the clock of the firmware, encoding one frame out of the ledstrip frames of each image.
+fn record<W: Write>(
+    options: &Options,
+    renderer: &DotRenderer,
+    recorder: &mut Recorder<W>,
+) -> io::Result<()> {
+    let mut simulation = Simulation::new(options.run.seed, options.run.rule, options.run.interval);
+    // Every ledstrip frame is rendered, as the idle animation advances by frame.
+    let frames_per_image = options.frames_per_image();
+    let mut next_generation = options.run.interval;
+    let mut frame = 0;
+    let mut now = 0;
+    while now < options.duration {
+        if now >= next_generation {
+            simulation.step(now);
+            next_generation = next_generation.saturating_add(options.run.interval);
+        }
+        let colors = simulation.render(now);
+        if frame % frames_per_image == 0 {
+            recorder.write_frame(&renderer.render(&simulation.strip(&colors)))?;
+        }
+        frame += 1;
+        now = now.saturating_add(FRAME_INTERVAL_US);
+    }
+    Ok(())
+}
@@ -0,0 +1,95 @@
+//! The command line options shared by the simulator binaries: the seed, the rule and the generation interval.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use gol_core::config::{GENERATION_INTERVAL_US, STARTUP_RULE};
+use gol_core::rule::Rule;
+
+/// How a simulation runs, from the command line.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RunOptions {
+    /// The seed of the board randomization.
+    pub seed: u32,
+    /// The rule of the automaton, `STARTUP_RULE` by default.
+    pub rule: Rule,
+    /// The interval between two generations, in µs, `GENERATION_INTERVAL_US` by default.
+    pub interval: u64,
+}
+
+impl RunOptions {
+    /// Creates the options of a run from `seed`, with the rule and the interval of the firmware.
+    pub const fn new(seed: u32) -> Self {
+        RunOptions {
+            seed,
+            rule: STARTUP_RULE,
+            interval: GENERATION_INTERVAL_US,
+        }
+    }
+
+    /// Applies `--seed`, `--rule` or `--interval`.
+    ///
+    /// # Arguments
+    ///
+    /// * `option`: The name of the option, e.g. `--seed`.
+    /// * `value`: The value following it.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Ok(false)` if `option` is none of them, so the caller can parse its own options.
+    pub fn parse_option(&mut self, option: &str, value: &str) -> Result<bool, String> {
+        match option {
+            "--seed" => {
+                // Seeds are logged in hexadecimal by the firmware.
+                let seed = match value.strip_prefix("0x") {
+                    Some(hex) => u32::from_str_radix(hex, 16),
+                    None => value.parse(),
+                };
+                self.seed = seed.map_err(|e| format!("invalid seed {}: {}", value, e))?
+            }
+            "--rule" => {
+                self.rule = value
+                    .parse()
+                    .map_err(|e| format!("invalid rule {}: {}", value, e))?
+            }
+            "--interval" => {
+                let ms: u64 = value
+                    .parse()
+                    .map_err(|e| format!("invalid interval {}: {}", value, e))?;
+                self.interval = ms.max(1) * 1000;
+            }
+            _ => return Ok(false),
+        }
+        Ok(true)
+    }
+}
+
+/// Draws a seed from the clock, like the firmware draws one from the ring oscillator.
+pub fn random_seed() -> u32 {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default();
+    (now.as_nanos() as u32) | 1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn run_options_are_parsed() {
+        let mut options = RunOptions::new(1);
+        assert_eq!(options.parse_option("--seed", "0x1234"), Ok(true));
+        assert_eq!(options.parse_option("--rule", "B36/S23"), Ok(true));
+        assert_eq!(options.parse_option("--interval", "250"), Ok(true));
+        assert_eq!(options.parse_option("--fps", "25"), Ok(false));
+        assert_eq!(options.seed, 0x1234);
+        assert_eq!(options.rule, "B36/S23".parse().unwrap());
+        assert_eq!(options.interval, 250_000);
+
+        assert_eq!(options.parse_option("--seed", "42"), Ok(true));
+        assert_eq!(options.seed, 42);
+        assert!(options.parse_option("--seed", "0xZZ").is_err());
+        assert!(options.parse_option("--rule", "B9/S").is_err());
+        assert!(options.parse_option("--interval", "-1").is_err());
+    }
+}
@@ -0,0 +1,9 @@
+//! Host-side simulator of pico-game-of-light.
+//!
+//! It runs the same `gol-core` pipeline as the firmware (game grid, cell ages, palettes, transitions, idle animation
+//! and ledstrip layout) with `std` timing, so a board can be watched without flashing a Pico.
+//! Run it in a terminal with `cargo sim-host`.
+
+pub mod cli;
+pub mod recorder;
+pub mod simulation;
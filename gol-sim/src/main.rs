@@ -0,0 +1,151 @@
+//! Terminal simulator: draws the LED matrix with 24-bit ANSI colors at the frame rate of the firmware.
+//!
+//! Usage: `cargo sim-host -- [--seed <u32>] [--rule <B/S rulestring>] [--interval <ms>]`
+//!
+//! Keys: `space` pauses or resumes the generations, `s` computes one generation, `r` reseeds the board,
+//! `p` switches to the next palette and `q` quits.
+
+use std::io::{self, Stdout, Write};
+use std::time::{Duration, Instant};
+
+use cichlid::ColorRGB;
+use crossterm::cursor::{Hide, MoveTo, MoveToNextLine, Show};
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyModifiers};
+use crossterm::style::{Color, Print, ResetColor, SetForegroundColor};
+use crossterm::terminal::{self, Clear, ClearType, EnterAlternateScreen, LeaveAlternateScreen};
+use crossterm::{execute, queue};
+use gol_core::config::{FRAME_INTERVAL_US, GRID_HEIGHT, GRID_WIDTH, LEDSTRIP_LENGTH, LED_LAYOUT};
+use gol_core::layout::LedLayout;
+use gol_sim::cli::{random_seed, RunOptions};
+use gol_sim::simulation::Simulation;
+
+const USAGE: &str = "usage: gol-sim [--seed <u32>] [--rule <B/S rulestring>] [--interval <ms>]";
+
+/// Parses the options of a run from the command line.
+fn parse_options(mut args: impl Iterator<Item = String>) -> Result<RunOptions, String> {
+    let mut options = RunOptions::new(random_seed());
+    while let Some(arg) = args.next() {
+        let value = args
+            .next()
+            .ok_or_else(|| format!("missing value after {}", arg))?;
+        if !options.parse_option(&arg, &value)? {
+            return Err(format!("unknown option {}", arg));
+        }
+    }
+    Ok(options)
+}
+
+fn main() {
+    let options = match parse_options(std::env::args().skip(1)) {
+        Ok(options) => options,
+        Err(error) => {
+            eprintln!("{}\n{}", error, USAGE);
+            std::process::exit(2);
+        }
+    };
+    let mut out = io::stdout();
+    let result = terminal::enable_raw_mode()
+        .and_then(|()| execute!(out, EnterAlternateScreen, Hide))
+        .and_then(|()| run(&mut out, options));
+    let _ = execute!(out, ResetColor, Show, LeaveAlternateScreen);
+    let _ = terminal::disable_raw_mode();
+    if let Err(error) = result {
+        eprintln!("gol-sim: {}", error);
+        std::process::exit(1);
+    }
+}
+
+/// Runs the simulation until `q` is pressed.
+fn run(out: &mut Stdout, options: RunOptions) -> io::Result<()> {
+    let start = Instant::now();
+    let now = || start.elapsed().as_micros() as u64;
+    let mut simulation = Simulation::new(options.seed, options.rule, options.interval);
+    let mut next_generation = options.interval;
+    let mut next_frame: u64 = 0;
+    let mut paused = false;
+    queue!(out, Clear(ClearType::All))?;
+
+    loop {
+        // Wait for the next frame, handling the keys pressed meanwhile.
+        while event::poll(Duration::from_micros(next_frame.saturating_sub(now())))? {
+            if let Event::Key(KeyEvent {
+                code, modifiers, ..
+            }) = event::read()?
+            {
+                match code {
+                    KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+                    KeyCode::Char('c') if modifiers.contains(KeyModifiers::CONTROL) => {
+                        return Ok(())
+                    }
+                    KeyCode::Char(' ') => {
+                        paused = !paused;
+                        next_generation = now() + options.interval;
+                    }
+                    KeyCode::Char('s') => {
+                        simulation.step(now());
+                        next_generation = now() + options.interval;
+                    }
+                    KeyCode::Char('r') => {
+                        simulation.reseed(now());
+                        next_generation = now() + options.interval;
+                    }
+                    KeyCode::Char('p') => {
+                        simulation.next_palette();
+                    }
+                    _ => {}
+                }
+            }
+        }
+        next_frame += FRAME_INTERVAL_US;
+
+        if !paused && now() >= next_generation {
+            simulation.step(now());
+            next_generation += options.interval;
+        }
+        let colors = simulation.render(now());
+        draw(out, &simulation, &simulation.strip(&colors), paused)?;
+    }
+}
+
+/// Draws the LEDs where they sit on the board, then the state of the simulation.
+fn draw(
+    out: &mut Stdout,
+    simulation: &Simulation,
+    strip: &[ColorRGB; LEDSTRIP_LENGTH],
+    paused: bool,
+) -> io::Result<()> {
+    queue!(out, MoveTo(0, 0))?;
+    for y in 0..GRID_HEIGHT {
+        for x in 0..GRID_WIDTH {
+            let color = LED_LAYOUT
+                .led_index(x, y)
+                .map_or(ColorRGB::Black, |led| strip[led]);
+            queue!(
+                out,
+                SetForegroundColor(Color::Rgb {
+                    r: color.r,
+                    g: color.g,
+                    b: color.b,
+                }),
+                Print("● ")
+            )?;
+        }
+        queue!(out, ResetColor, MoveToNextLine(1))?;
+    }
+    queue!(
+        out,
+        MoveToNextLine(1),
+        Clear(ClearType::CurrentLine),
+        Print(format!(
+            "generation {}  rule {}  seed {:#x}  palette {}{}",
+            simulation.generation(),
+            simulation.rule(),
+            simulation.seed(),
+            simulation.palette_name(),
+            if paused { "  [paused]" } else { "" }
+        )),
+        MoveToNextLine(1),
+        Print("space: pause  s: step  r: reseed  p: palette  q: quit"),
+    )?;
+    out.flush()
+}
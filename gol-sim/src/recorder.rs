@@ -0,0 +1,383 @@
+//! Rasterizes ledstrip frames into images of the board and encodes them as animated GIF or APNG.
+
+use std::io::{self, Write};
+use std::path::Path;
+
+use cichlid::ColorRGB;
+use gol_core::layout::LedLayout;
+
+use gol_core::config::{GRID_HEIGHT, GRID_WIDTH, LEDSTRIP_LENGTH, LED_LAYOUT};
+
+/// The color of the board between the LEDs.
+pub const BACKGROUND: ColorRGB = ColorRGB {
+    r: 16,
+    g: 16,
+    b: 16,
+};
+
+/// Draws the LEDs of the ledstrip as round dots, where they sit on the board.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DotRenderer {
+    /// The distance between the centers of two neighbouring LEDs, in pixels.
+    pitch: usize,
+}
+
+impl DotRenderer {
+    /// Creates a renderer drawing each LED in a square of `pitch` by `pitch` pixels.
+    ///
+    /// # Panics
+    ///
+    /// If `pitch` is `0`.
+    pub const fn new(pitch: usize) -> Self {
+        assert!(pitch > 0, "the LEDs need at least a pixel");
+        DotRenderer { pitch }
+    }
+
+    /// Returns the width of the images, in pixels.
+    pub const fn width(&self) -> usize {
+        GRID_WIDTH * self.pitch
+    }
+
+    /// Returns the height of the images, in pixels.
+    pub const fn height(&self) -> usize {
+        GRID_HEIGHT * self.pitch
+    }
+
+    /// Draws a frame sent to the ledstrip.
+    ///
+    /// # Returns
+    ///
+    /// The pixels of the image, row by row from the top left corner, three bytes (red, green, blue) each.
+    pub fn render(&self, strip: &[ColorRGB; LEDSTRIP_LENGTH]) -> Vec<u8> {
+        let (width, pitch) = (self.width(), self.pitch);
+        let mut pixels = vec![0; width * self.height() * 3];
+        let center = pitch as f32 / 2.0;
+        let radius = pitch as f32 * 0.4;
+        // The coverage of the pixels by a dot is the same for every LED, with antialiased edges.
+        let coverage: Vec<u16> = (0..pitch * pitch)
+            .map(|i| {
+                let (dx, dy) = ((i % pitch) as f32 + 0.5, (i / pitch) as f32 + 0.5);
+                let distance = ((dx - center).powi(2) + (dy - center).powi(2)).sqrt();
+                ((radius + 0.5 - distance).clamp(0.0, 1.0) * 256.0) as u16
+            })
+            .collect();
+
+        (0..GRID_HEIGHT).for_each(|y| {
+            (0..GRID_WIDTH).for_each(|x| {
+                let color = LED_LAYOUT
+                    .led_index(x, y)
+                    .and_then(|led| strip.get(led))
+                    .copied()
+                    .unwrap_or(BACKGROUND);
+                (0..pitch * pitch).for_each(|i| {
+                    let (px, py) = (x * pitch + i % pitch, y * pitch + i / pitch);
+                    let pixel = &mut pixels[(py * width + px) * 3..][..3];
+                    let mix = |led: u8, board: u8| {
+                        ((led as u16 * coverage[i] + board as u16 * (256 - coverage[i])) >> 8) as u8
+                    };
+                    pixel[0] = mix(color.r, BACKGROUND.r);
+                    pixel[1] = mix(color.g, BACKGROUND.g);
+                    pixel[2] = mix(color.b, BACKGROUND.b);
+                });
+            });
+        });
+        pixels
+    }
+}
+
+/// The file format of a recording.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    /// An animated GIF: widely supported, but limited to 256 colors per frame and delays in hundredths of a second.
+    Gif,
+    /// An animated PNG: lossless colors and exact delays.
+    Apng,
+}
+
+impl Format {
+    /// Picks the format from the extension of `path`, `.gif` or `.png`.
+    pub fn from_path(path: &Path) -> Option<Format> {
+        let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+        match extension.as_str() {
+            "gif" => Some(Format::Gif),
+            "png" | "apng" => Some(Format::Apng),
+            _ => None,
+        }
+    }
+
+    /// Returns the largest width and height of the images, in pixels.
+    pub const fn max_size(&self) -> usize {
+        match self {
+            Format::Gif => u16::MAX as usize,
+            Format::Apng => u32::MAX as usize,
+        }
+    }
+}
+
+/// The images of a run, all the same size and shown for the same time.
+pub struct Recording {
+    /// The width of the images, in pixels.
+    pub width: usize,
+    /// The height of the images, in pixels.
+    pub height: usize,
+    /// The time each image is shown, in µs.
+    pub frame_interval: u64,
+    /// The number of images, announced by the header of APNG files.
+    pub frames: u64,
+}
+
+impl Recording {
+    /// Starts encoding the recording as an animated image looping forever, its images are written as they are drawn.
+    ///
+    /// # Arguments
+    ///
+    /// * `writer`: Where the file is written.
+    /// * `format`: The format of the file.
+    ///
+    /// # Returns
+    ///
+    /// Returns an `InvalidInput` error if the recording has no frame, more frames than an APNG file counts or images
+    /// larger than `Format::max_size`, as the format cannot hold them.
+    pub fn start<W: Write>(&self, writer: W, format: Format) -> io::Result<Recorder<W>> {
+        if self.frames == 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "a recording needs at least one frame",
+            ));
+        }
+        if self.width > format.max_size() || self.height > format.max_size() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "images of {}x{} pixels do not fit in a {:?} file",
+                    self.width, self.height, format
+                ),
+            ));
+        }
+        let encoder = match format {
+            Format::Gif => self.start_gif(writer)?,
+            Format::Apng => self.start_apng(writer)?,
+        };
+        Ok(Recorder {
+            width: self.width,
+            height: self.height,
+            encoder,
+        })
+    }
+
+    fn start_gif<W: Write>(&self, writer: W) -> io::Result<Encoder<W>> {
+        // `start` checked that the size fits.
+        let (width, height) = (self.width as u16, self.height as u16);
+        let mut encoder = gif::Encoder::new(writer, width, height, &[]).map_err(gif_error)?;
+        encoder
+            .set_repeat(gif::Repeat::Infinite)
+            .map_err(gif_error)?;
+        // GIF delays are in hundredths of a second.
+        let delay = ((self.frame_interval + 5_000) / 10_000).max(1) as u16;
+        Ok(Encoder::Gif { encoder, delay })
+    }
+
+    fn start_apng<W: Write>(&self, writer: W) -> io::Result<Encoder<W>> {
+        let frames = u32::try_from(self.frames).map_err(|_| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("{} frames do not fit in an Apng file", self.frames),
+            )
+        })?;
+        let mut encoder = png::Encoder::new(writer, self.width as u32, self.height as u32);
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+        encoder.set_animated(frames, 0).map_err(png_error)?;
+        // The encoder checks that the images written are the ones announced.
+        encoder.validate_sequence(true);
+        // The delay is a fraction of a second, reduced to fit its 16 bit numerator and denominator.
+        let divisor = gcd(self.frame_interval.max(1), 1_000_000);
+        let (mut numerator, mut denominator) = (self.frame_interval / divisor, 1_000_000 / divisor);
+        while numerator > u16::MAX as u64 || denominator > u16::MAX as u64 {
+            numerator /= 10;
+            denominator /= 10;
+        }
+        encoder
+            .set_frame_delay(numerator as u16, denominator as u16)
+            .map_err(png_error)?;
+        Ok(Encoder::Apng(encoder.write_header().map_err(png_error)?))
+    }
+}
+
+/// Encodes the images of a `Recording` one by one, so only the image being drawn is kept in memory.
+pub struct Recorder<W: Write> {
+    width: usize,
+    height: usize,
+    encoder: Encoder<W>,
+}
+
+enum Encoder<W: Write> {
+    Gif {
+        encoder: gif::Encoder<W>,
+        /// The time each image is shown, in hundredths of a second.
+        delay: u16,
+    },
+    Apng(png::Writer<W>),
+}
+
+impl<W: Write> Recorder<W> {
+    /// Encodes the next image of the recording.
+    ///
+    /// # Arguments
+    ///
+    /// * `pixels`: The image, as returned by `DotRenderer::render`.
+    pub fn write_frame(&mut self, pixels: &[u8]) -> io::Result<()> {
+        match &mut self.encoder {
+            Encoder::Gif { encoder, delay } => {
+                let (width, height) = (self.width as u16, self.height as u16);
+                let mut frame = gif::Frame::from_rgb_speed(width, height, pixels, 10);
+                frame.delay = *delay;
+                encoder.write_frame(&frame).map_err(gif_error)
+            }
+            Encoder::Apng(writer) => writer.write_image_data(pixels).map_err(png_error),
+        }
+    }
+
+    /// Ends the file and flushes it.
+    ///
+    /// # Returns
+    ///
+    /// Returns an `InvalidData` error if an APNG file got fewer images than `Recording::frames`.
+    pub fn finish(self) -> io::Result<()> {
+        match self.encoder {
+            Encoder::Gif { encoder, .. } => encoder.into_inner()?.flush(),
+            Encoder::Apng(writer) => writer.finish().map_err(png_error),
+        }
+    }
+}
+
+fn gcd(a: u64, b: u64) -> u64 {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+fn gif_error(error: gif::EncodingError) -> io::Error {
+    match error {
+        gif::EncodingError::Io(error) => error,
+        error => io::Error::new(io::ErrorKind::InvalidData, error),
+    }
+}
+
+fn png_error(error: png::EncodingError) -> io::Error {
+    match error {
+        png::EncodingError::IoError(error) => error,
+        error => io::Error::new(io::ErrorKind::InvalidData, error),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gol_core::config::{NUM_LEDS, PADDING_COLOR};
+
+    fn strip(colors: &[ColorRGB; NUM_LEDS]) -> [ColorRGB; LEDSTRIP_LENGTH] {
+        let mut leds = [PADDING_COLOR; LEDSTRIP_LENGTH];
+        LED_LAYOUT.arrange(colors, &mut leds);
+        leds
+    }
+
+    const RENDERER: DotRenderer = DotRenderer::new(4);
+
+    fn recording(frames: u64) -> Recording {
+        Recording {
+            width: RENDERER.width(),
+            height: RENDERER.height(),
+            frame_interval: 40_000,
+            frames,
+        }
+    }
+
+    /// Records `frames` images, each lighting the next LED.
+    fn record(frames: usize, format: Format) -> Vec<u8> {
+        let mut file = Vec::new();
+        let mut recorder = recording(frames as u64).start(&mut file, format).unwrap();
+        (0..frames).for_each(|i| {
+            let mut colors = [ColorRGB::Black; NUM_LEDS];
+            colors[i] = ColorRGB::White;
+            recorder
+                .write_frame(&RENDERER.render(&strip(&colors)))
+                .unwrap();
+        });
+        recorder.finish().unwrap();
+        file
+    }
+
+    #[test]
+    fn leds_are_drawn_as_dots_where_they_sit() {
+        let renderer = DotRenderer::new(10);
+        let mut colors = [ColorRGB::Black; NUM_LEDS];
+        colors[GRID_WIDTH + 2] = ColorRGB::White;
+        let pixels = renderer.render(&strip(&colors));
+        assert_eq!(pixels.len(), 170 * 80 * 3);
+        let pixel = |x: usize, y: usize| &pixels[(y * renderer.width() + x) * 3..][..3];
+        // The center of the dot of the cell (2, 1), the corner of its square and the center of a black cell.
+        assert_eq!(pixel(25, 15), [255, 255, 255]);
+        assert_eq!(pixel(20, 10), [16, 16, 16]);
+        assert_eq!(pixel(15, 15), [0, 0, 0]);
+    }
+
+    #[test]
+    fn formats_follow_the_extension() {
+        assert_eq!(Format::from_path(Path::new("run.gif")), Some(Format::Gif));
+        assert_eq!(Format::from_path(Path::new("run.PNG")), Some(Format::Apng));
+        assert_eq!(Format::from_path(Path::new("run.mp4")), None);
+        assert_eq!(Format::from_path(Path::new("run")), None);
+    }
+
+    #[test]
+    fn gif_holds_every_frame() {
+        let file = record(3, Format::Gif);
+        let mut decoder = gif::DecodeOptions::new().read_info(&file[..]).unwrap();
+        let mut delays = Vec::new();
+        while let Some(frame) = decoder.read_next_frame().unwrap() {
+            delays.push(frame.delay);
+        }
+        assert_eq!(delays, [4, 4, 4]);
+    }
+
+    #[test]
+    fn apng_holds_every_frame() {
+        let file = record(3, Format::Apng);
+        let decoder = png::Decoder::new(&file[..]);
+        let reader = decoder.read_info().unwrap();
+        let animation = reader.info().animation_control.unwrap();
+        assert_eq!(animation.num_frames, 3);
+        let control = reader.info().frame_control.unwrap();
+        assert_eq!((control.delay_num, control.delay_den), (1, 25));
+    }
+
+    #[test]
+    fn apng_missing_frames_is_an_error() {
+        let mut recorder = recording(2).start(Vec::new(), Format::Apng).unwrap();
+        recorder
+            .write_frame(&RENDERER.render(&[ColorRGB::Black; LEDSTRIP_LENGTH]))
+            .unwrap();
+        let error = recorder.finish().unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn recordings_that_do_not_fit_are_rejected() {
+        let error = recording(0).start(Vec::new(), Format::Apng).err().unwrap();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+
+        let error = recording(u32::MAX as u64 + 1)
+            .start(Vec::new(), Format::Apng)
+            .err()
+            .unwrap();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+
+        let mut wide = recording(1);
+        wide.width = u16::MAX as usize + 1;
+        let error = wide.start(Vec::new(), Format::Gif).err().unwrap();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+    }
+}
@@ -0,0 +1,205 @@
+use cichlid::ColorRGB;
+use gol_core::config::*;
+use gol_core::frame::{FrameStep, Generation};
+use gol_core::game::Game;
+use gol_core::layout::LedLayout;
+use gol_core::palette::{CrossFade, Palette};
+use gol_core::rule::Rule;
+use gol_core::timing::TransitionClock;
+
+/// A board and its ledstrip animation, driven by the caller's clock.
+///
+/// The board is run by the `Game` of the firmware, so a seed replays the run of the ledstrip.
+/// Times are in microseconds since any fixed origin, e.g. the start of the simulation.
+pub struct Simulation {
+    game: Game<GRID_WIDTH, GRID_HEIGHT, HASH_HISTORY_LEN>,
+    rule: Rule,
+    seed: u32,
+    /// The generations sent to the animation, like the `GameGridMessage` of the firmware.
+    previous: Generation<NUM_LEDS>,
+    current: Generation<NUM_LEDS>,
+    generation: u64,
+    palette: usize,
+    fade: CrossFade<'static>,
+    clock: TransitionClock,
+    interval: u64,
+    /// Frames since the start, drives the idle animation.
+    tick: u32,
+}
+
+impl Simulation {
+    /// Creates a randomized board, the way the firmware does at boot.
+    ///
+    /// # Arguments
+    ///
+    /// * `seed`: The seed of the board randomization, runs with the same seed are identical.
+    /// * `rule`: The rule of the automaton.
+    /// * `interval`: The interval between two generations, in µs, which sets the length of the transitions.
+    pub fn new(seed: u32, rule: Rule, interval: u64) -> Self {
+        let palette = Palette::ALL
+            .iter()
+            .position(|&(_, palette)| palette == STARTUP_PALETTE)
+            .unwrap_or(0);
+        let mut simulation = Simulation {
+            game: Game::new(&GAME_SETTINGS, seed),
+            rule,
+            seed,
+            previous: Generation::EMPTY,
+            current: Generation::EMPTY,
+            generation: 0,
+            palette,
+            fade: CrossFade::new(Palette::ALL[palette].1, PALETTE_FADE_STEPS),
+            clock: TransitionClock::new(TRANSITION_TIMING),
+            interval,
+            tick: 0,
+        };
+        simulation.publish(0);
+        simulation
+    }
+
+    /// Computes the next generation and starts its transition, reseeding a stagnating board.
+    ///
+    /// # Arguments
+    ///
+    /// * `now`: The current time, the start of the transition.
+    pub fn step(&mut self, now: u64) {
+        self.game.step(&self.rule);
+        self.generation += 1;
+        if self.game.should_reseed() {
+            self.game.reseed();
+        }
+        self.publish(now);
+    }
+
+    /// Randomizes the board again, from the random number generator of the run.
+    pub fn reseed(&mut self, now: u64) {
+        self.game.reseed();
+        self.publish(now);
+    }
+
+    /// Cross-fades to the next built-in palette, see `Palette::ALL`.
+    ///
+    /// # Returns
+    ///
+    /// The name of the new palette.
+    pub fn next_palette(&mut self) -> &'static str {
+        self.palette = (self.palette + 1) % Palette::ALL.len();
+        self.fade.switch_to(Palette::ALL[self.palette].1);
+        self.palette_name()
+    }
+
+    /// Computes the colors of the cells for the frame at `now`, in the row-major order of the game grid.
+    pub fn render(&mut self, now: u64) -> [ColorRGB; NUM_LEDS] {
+        let step = FrameStep {
+            progress: self.clock.progress(now),
+            tick: self.tick,
+            fade: &self.fade,
+        };
+        let colors = FRAME_STYLE.render_frame(&self.previous, &self.current, &step);
+        self.fade.advance();
+        self.tick = self.tick.wrapping_add(1);
+        colors
+    }
+
+    /// Reorders the colors of the cells along `LED_LAYOUT`, as sent to the ledstrip.
+    pub fn strip(&self, colors: &[ColorRGB; NUM_LEDS]) -> [ColorRGB; LEDSTRIP_LENGTH] {
+        let mut leds = [PADDING_COLOR; LEDSTRIP_LENGTH];
+        LED_LAYOUT.arrange(colors, &mut leds);
+        leds
+    }
+
+    /// Returns the board.
+    pub fn board(&self) -> &Board {
+        self.game.board()
+    }
+
+    /// Returns the rule of the automaton.
+    pub fn rule(&self) -> Rule {
+        self.rule
+    }
+
+    /// Returns the seed of the run.
+    pub fn seed(&self) -> u32 {
+        self.seed
+    }
+
+    /// Returns the number of generations since the start.
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    /// Returns the name of the current palette.
+    pub fn palette_name(&self) -> &'static str {
+        Palette::ALL[self.palette].0
+    }
+
+    /// Hands the new generation to the animation, like `refresh_gol_board` signals a `GameGridMessage`.
+    fn publish(&mut self, now: u64) {
+        self.previous = self.current;
+        self.current = self.game.generation();
+        self.clock.start(now, self.interval);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frames(seed: u32) -> Vec<[ColorRGB; NUM_LEDS]> {
+        let mut simulation = Simulation::new(seed, Rule::default(), GENERATION_INTERVAL_US);
+        let mut frames = Vec::new();
+        for generation in 0..3 {
+            let start = generation * GENERATION_INTERVAL_US;
+            simulation.step(start);
+            frames.extend((0..10).map(|frame| simulation.render(start + frame * 100_000)));
+        }
+        frames
+    }
+
+    #[test]
+    fn runs_are_replayed_from_their_seed() {
+        assert!(frames(7) == frames(7));
+        assert!(frames(7) != frames(8));
+    }
+
+    #[test]
+    fn transitions_end_on_the_palette_colors() {
+        let mut simulation = Simulation::new(3, Rule::default(), GENERATION_INTERVAL_US);
+        simulation.step(0);
+        let colors = simulation.render(GENERATION_INTERVAL_US);
+        let (previous, current) = (simulation.previous, simulation.current);
+        let changed: Vec<usize> = (0..NUM_LEDS)
+            .filter(|&led| previous.alive[led] != current.alive[led])
+            .collect();
+        assert!(!changed.is_empty());
+        for led in changed {
+            let column = led % GRID_WIDTH;
+            let expected = Palette::CLASSIC.color(current.ages[led], column, GRID_WIDTH);
+            assert_eq!(colors[led], expected);
+        }
+    }
+
+    #[test]
+    fn strip_follows_the_layout() {
+        let simulation = Simulation::new(1, Rule::default(), GENERATION_INTERVAL_US);
+        let mut colors = [ColorRGB::Black; NUM_LEDS];
+        colors[GRID_WIDTH] = ColorRGB::White;
+        let strip = simulation.strip(&colors);
+        // The second row runs backwards.
+        let led = LED_LAYOUT.led_index(0, 1).unwrap();
+        assert_eq!(led, 2 * GRID_WIDTH - 1);
+        assert_eq!(strip[led], ColorRGB::White);
+        assert_eq!(strip.iter().filter(|&&c| c == ColorRGB::White).count(), 1);
+    }
+
+    #[test]
+    fn palettes_cycle() {
+        let mut simulation = Simulation::new(1, Rule::default(), GENERATION_INTERVAL_US);
+        assert_eq!(simulation.palette_name(), "classic");
+        assert_eq!(simulation.next_palette(), "fire");
+        for _ in 1..Palette::ALL.len() {
+            simulation.next_palette();
+        }
+        assert_eq!(simulation.palette_name(), "classic");
+    }
+}
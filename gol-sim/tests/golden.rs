@@ -0,0 +1,146 @@
+//! Golden-frame regression tests: the frames rendered for fixed seeds are compared with the files of `tests/golden`.
+//!
+//! After an intended change of the colors, regenerate the files with
+//! `UPDATE_GOLDEN=1 cargo test -p gol-sim --target x86_64-unknown-linux-gnu --test golden` and review their diff.
+
+use std::fmt::Write;
+use std::fs;
+use std::path::PathBuf;
+
+use cichlid::ColorRGB;
+use gol_core::config::NUM_LEDS;
+use gol_core::rule::Rule;
+use gol_sim::simulation::Simulation;
+
+/// The interval between two generations of the recorded runs, in µs.
+const INTERVAL: u64 = 1_000_000;
+
+/// The generations of a recorded run.
+const GENERATIONS: u64 = 4;
+
+/// The frames recorded at the start of each generation, spanning its transition and the beginning of the idle animation.
+const FRAMES_PER_GENERATION: u64 = 8;
+
+/// The time between two recorded frames, in µs.
+const FRAME_INTERVAL: u64 = 40_000;
+
+/// A recorded run.
+struct Scenario {
+    /// The name of the golden file, in `tests/golden`.
+    name: &'static str,
+    seed: u32,
+    rule: &'static str,
+    /// The generation at whose start the next palette is switched to, if any.
+    palette_switch: Option<u64>,
+}
+
+const SCENARIOS: [Scenario; 3] = [
+    Scenario {
+        name: "conway_0x1",
+        seed: 0x1,
+        rule: "B3/S23",
+        palette_switch: None,
+    },
+    Scenario {
+        name: "highlife_0x1234",
+        seed: 0x1234,
+        rule: "B36/S23",
+        palette_switch: None,
+    },
+    Scenario {
+        name: "palette_switch_0x2a",
+        seed: 0x2a,
+        rule: "B3/S23",
+        palette_switch: Some(1),
+    },
+];
+
+/// Renders the frames of a scenario, one line of hexadecimal colors per frame.
+fn render(scenario: &Scenario) -> String {
+    let rule: Rule = scenario.rule.parse().unwrap();
+    let mut simulation = Simulation::new(scenario.seed, rule, INTERVAL);
+    let mut text = format!(
+        "# seed {:#x}, rule {}, {} frames per generation every {} µs\n",
+        scenario.seed, scenario.rule, FRAMES_PER_GENERATION, FRAME_INTERVAL
+    );
+    (0..GENERATIONS).for_each(|generation| {
+        let start = generation * INTERVAL;
+        if generation > 0 {
+            simulation.step(start);
+        }
+        if scenario.palette_switch == Some(generation) {
+            simulation.next_palette();
+        }
+        (0..FRAMES_PER_GENERATION).for_each(|frame| {
+            let colors: [ColorRGB; NUM_LEDS] = simulation.render(start + frame * FRAME_INTERVAL);
+            let line: Vec<String> = colors
+                .iter()
+                .map(|c| format!("{:02x}{:02x}{:02x}", c.r, c.g, c.b))
+                .collect();
+            writeln!(text, "{}", line.join(" ")).unwrap();
+        });
+    });
+    text
+}
+
+fn golden_path(scenario: &Scenario) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/golden")
+        .join(scenario.name)
+        .with_extension("txt")
+}
+
+/// Describes the first difference between two renderings.
+fn first_difference(expected: &str, actual: &str) -> String {
+    for (line, (expected, actual)) in expected.lines().zip(actual.lines()).enumerate() {
+        let pairs = expected.split(' ').zip(actual.split(' '));
+        if let Some((led, (expected, actual))) = pairs.enumerate().find(|(_, (e, a))| e != a) {
+            return format!(
+                "line {}, LED {}: expected {}, got {}",
+                line + 1,
+                led,
+                expected,
+                actual
+            );
+        }
+    }
+    format!(
+        "expected {} lines, got {}",
+        expected.lines().count(),
+        actual.lines().count()
+    )
+}
+
+#[test]
+fn frames_match_the_golden_files() {
+    let update = std::env::var_os("UPDATE_GOLDEN").is_some();
+    let mut failures = Vec::new();
+    for scenario in &SCENARIOS {
+        let path = golden_path(scenario);
+        let actual = render(scenario);
+        if update {
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(&path, &actual).unwrap();
+            continue;
+        }
+        match fs::read_to_string(&path) {
+            Ok(expected) if expected == actual => {}
+            Ok(expected) => failures.push(format!(
+                "{}: {}",
+                scenario.name,
+                first_difference(&expected, &actual)
+            )),
+            Err(error) => failures.push(format!("{}: {}", path.display(), error)),
+        }
+    }
+    assert!(
+        failures.is_empty(),
+        "rendered frames differ from the golden files, rerun with UPDATE_GOLDEN=1 if intended:\n{}",
+        failures.join("\n")
+    );
+}
+
+#[test]
+fn renderings_are_deterministic() {
+    assert_eq!(render(&SCENARIOS[0]), render(&SCENARIOS[0]));
+}
@@ -0,0 +1,102 @@
+use embassy_futures::select::{select, Either};
+use embassy_time::{Duration, Instant, Timer};
+use gol_core::frame::{FrameStep, FrameStyle};
+use gol_core::palette::CrossFade;
+use gol_core::timing::{TransitionClock, TransitionTiming};
+
+use crate::channels::{Channels, GameGridMessage, LedStripMessage};
+
+/// How `animate_ledstrip` draws the frames.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AnimationSettings {
+    /// How the frames are drawn from the generations of the game grid.
+    pub style: FrameStyle,
+    /// How long the births, deaths and age changes of the cells last, as a part of the generation interval.
+    pub timing: TransitionTiming,
+    /// The number of animation frames over which a new palette is cross-faded in.
+    pub palette_fade_steps: usize,
+    /// The delay between two frames.
+    pub frame_delay: Duration,
+}
+
+/// Animates a LED strip based on updates to a game grid.
+///
+/// This function listens for the `GameGridMessage`s of `refresh_gol_board` and for the palette switches of `channels`.
+/// It animates the LED strip based on the difference between the current and previous game grid states:
+/// each LED blends from the color of the previous state of its cell to the one of its new state, see `FrameStyle`.
+/// Transitions last `AnimationSettings::timing` of the generation interval: the colors of each frame are computed
+/// from the time elapsed since the update, so transitions keep their length whatever the frame rate.
+/// The colors come from the palette of `channels`, a new palette is cross-faded in over
+/// `AnimationSettings::palette_fade_steps` animation steps, see `Channels::set_palette`.
+/// Between two updates, frames keep being sent as long as the idle animation of the style or a palette cross-fade
+/// changes the colors.
+/// Each frame is drawn by `FrameStyle::render_frame`, from the two last generations only.
+///
+/// # Arguments
+///
+/// * `channels`: The channels shared with the other tasks.
+/// * `settings`: How the frames are drawn.
+/// * `interval`: The interval between two updates of the game grid, see `refresh_gol_board`.
+pub async fn animate_ledstrip<const N: usize>(
+    channels: &Channels<N>,
+    settings: &AnimationSettings,
+    interval: Duration,
+) {
+    let mut fade = CrossFade::new(channels.palette(), settings.palette_fade_steps);
+    let mut gamegrid_msg = GameGridMessage::EMPTY;
+    let mut prev_gamegrid_msg = GameGridMessage::EMPTY;
+    let mut clock = TransitionClock::new(settings.timing);
+    // Frames since startup, drives the idle animation.
+    let mut tick: u32 = 0;
+
+    loop {
+        match select(channels.game_grid.wait(), channels.palette_switch.wait()).await {
+            Either::First(msg) => {
+                // receive gg update and store last one
+                prev_gamegrid_msg = gamegrid_msg;
+                gamegrid_msg = msg;
+
+                // test purpose
+                let tmp: [u8; N] = gamegrid_msg.alive.map(|v| if v { 1 } else { 0 });
+                debug!("RECEIVED update of game grid:\n\t\t{}", tmp);
+
+                clock.start(Instant::now().as_micros(), interval.as_micros());
+            }
+            // Only the palette changed, the frames are drawn until it is cross-faded in.
+            Either::Second(()) => {}
+        }
+
+        // compute new colors: the transition, then the idle animation until the next update
+        loop {
+            let now = Instant::now().as_micros();
+            // A palette switched from now on raises the signal again, to be seen by the next frame.
+            channels.palette_switch.reset();
+            if fade.switch_to(channels.palette()) {
+                info!("Palette switched, cross-fading");
+            }
+            let step = FrameStep {
+                progress: clock.progress(now),
+                tick,
+                fade: &fade,
+            };
+            let ledstrip_msg = LedStripMessage {
+                led_strip_update: settings.style.render_frame(
+                    &prev_gamegrid_msg,
+                    &gamegrid_msg,
+                    &step,
+                ),
+            };
+            // The frame just drawn stays on the ledstrip if neither the idle animation nor the cross-fade changes it.
+            let still = !settings.style.idle.is_animated() && !fade.is_fading();
+            fade.advance();
+            tick = tick.wrapping_add(1);
+            //signal light ledstrip
+            channels.led_strip.signal(ledstrip_msg);
+            // A pending generation starts its transition, the cross-fade goes on over it.
+            if clock.is_done(now) && (still || channels.game_grid.signaled()) {
+                break;
+            }
+            Timer::after(settings.frame_delay).await;
+        }
+    }
+}
@@ -0,0 +1,86 @@
+use core::cell::Cell;
+
+use cichlid::ColorRGB;
+use embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex;
+use embassy_sync::blocking_mutex::Mutex;
+use embassy_sync::signal::Signal;
+use gol_core::frame::Generation;
+use gol_core::palette::Palette;
+use gol_core::rule::Rule;
+
+/// The state of the cells sent by `refresh_gol_board` to `animate_ledstrip` at each generation.
+pub type GameGridMessage<const N: usize> = Generation<N>;
+
+/// The colors sent by `animate_ledstrip` to `refresh_ledstrip` at each frame.
+pub struct LedStripMessage<const N: usize> {
+    /// The color of each cell, in the row-major order of the game grid.
+    pub led_strip_update: [ColorRGB; N],
+}
+
+/// The signals between the tasks, and the settings that can be changed while they run.
+///
+/// It is meant to be a `static`, shared by the tasks of a board of `N` cells.
+pub struct Channels<const N: usize> {
+    pub(crate) game_grid: Signal<CriticalSectionRawMutex, GameGridMessage<N>>,
+    pub(crate) led_strip: Signal<CriticalSectionRawMutex, LedStripMessage<N>>,
+    /// Raised by `set_palette`, so `animate_ledstrip` cross-fades the new palette in even between two generations.
+    pub(crate) palette_switch: Signal<CriticalSectionRawMutex, ()>,
+    /// The rule applied by `refresh_gol_board` at each generation.
+    rule: Mutex<CriticalSectionRawMutex, Cell<Rule>>,
+    /// The palette used by `animate_ledstrip`.
+    palette: Mutex<CriticalSectionRawMutex, Cell<Palette<'static>>>,
+    /// The global brightness of the ledstrip, applied by the output stage of `refresh_ledstrip`.
+    brightness: Mutex<CriticalSectionRawMutex, Cell<u8>>,
+}
+
+impl<const N: usize> Channels<N> {
+    /// Creates the channels of tasks starting with the given settings.
+    ///
+    /// # Arguments
+    ///
+    /// * `rule`: The rule of the automaton at startup.
+    /// * `palette`: The palette of the ledstrip at startup.
+    /// * `brightness`: The global brightness of the ledstrip at startup.
+    pub const fn new(rule: Rule, palette: Palette<'static>, brightness: u8) -> Self {
+        Channels {
+            game_grid: Signal::new(),
+            led_strip: Signal::new(),
+            palette_switch: Signal::new(),
+            rule: Mutex::new(Cell::new(rule)),
+            palette: Mutex::new(Cell::new(palette)),
+            brightness: Mutex::new(Cell::new(brightness)),
+        }
+    }
+
+    /// Returns the rule of the automaton.
+    pub fn rule(&self) -> Rule {
+        self.rule.lock(|r| r.get())
+    }
+
+    /// Switches the rule of the automaton, it takes effect at the next generation.
+    pub fn set_rule(&self, rule: Rule) {
+        self.rule.lock(|r| r.set(rule));
+    }
+
+    /// Returns the palette of the ledstrip.
+    pub fn palette(&self) -> Palette<'static> {
+        self.palette.lock(|p| p.get())
+    }
+
+    /// Switches the palette of the ledstrip, it is cross-faded in by the next animation steps.
+    pub fn set_palette(&self, palette: Palette<'static>) {
+        self.palette.lock(|p| p.set(palette));
+        self.palette_switch.signal(());
+    }
+
+    /// Returns the global brightness of the ledstrip.
+    pub fn brightness(&self) -> u8 {
+        self.brightness.lock(|b| b.get())
+    }
+
+    /// Sets the global brightness of the ledstrip, from `0` (off) to `255` (full intensity), it takes effect at the
+    /// next frame.
+    pub fn set_brightness(&self, brightness: u8) {
+        self.brightness.lock(|b| b.set(brightness));
+    }
+}
@@ -0,0 +1,41 @@
+//! Logging macros: they forward to defmt with the `defmt` feature, and only borrow their arguments without it.
+#![macro_use]
+#![allow(unused_macros)]
+
+macro_rules! debug {
+    ($s:literal $(, $x:expr)* $(,)?) => {{
+        #[cfg(feature = "defmt")]
+        ::defmt::debug!($s $(, $x)*);
+        #[cfg(not(feature = "defmt"))]
+        let _ = ($( & $x ),*);
+    }};
+}
+
+macro_rules! info {
+    ($s:literal $(, $x:expr)* $(,)?) => {{
+        #[cfg(feature = "defmt")]
+        ::defmt::info!($s $(, $x)*);
+        #[cfg(not(feature = "defmt"))]
+        let _ = ($( & $x ),*);
+    }};
+}
+
+macro_rules! warn {
+    ($s:literal $(, $x:expr)* $(,)?) => {{
+        #[cfg(feature = "defmt")]
+        ::defmt::warn!($s $(, $x)*);
+        #[cfg(not(feature = "defmt"))]
+        let _ = ($( & $x ),*);
+    }};
+}
+
+/// Logs a value through its `core::fmt::Display` implementation, like `defmt::Display2Format`.
+#[cfg_attr(not(feature = "defmt"), allow(dead_code))]
+pub(crate) struct Display<'a, T: core::fmt::Display>(pub &'a T);
+
+#[cfg(feature = "defmt")]
+impl<T: core::fmt::Display> defmt::Format for Display<'_, T> {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(f, "{}", defmt::Display2Format(self.0))
+    }
+}
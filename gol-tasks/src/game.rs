@@ -0,0 +1,115 @@
+use embassy_time::{Duration, Timer};
+use gol_core::export::{write_rle, TextBuffer};
+use gol_core::game::{Game, GameSettings, RESEED_DENSITY, STARTUP_DENSITY};
+use gol_core::game_grid::GameGrid;
+use gol_core::rule::Rule;
+use gol_core::stagnation::Stagnation;
+
+use crate::channels::Channels;
+use crate::fmt::Display;
+
+/// The capacity of the buffer the board is dumped into, as RLE.
+const DUMP_BUFFER_LEN: usize = 512;
+
+/// Updates a Game of Life board at a given interval and signals the changes to `animate_ledstrip` as a
+/// `GameGridMessage`.
+///
+/// This function runs a `Game`, which randomizes the board with a `STARTUP_DENSITY` chance of each cell being alive.
+/// The random generator seed is logged at startup, so a run can be replayed.
+/// Once the boardgame is stuck in a still life or an oscillator for as long as `GameSettings::reseed_policy` allows,
+/// the board is randomized again.
+/// Each generation is computed with the rule of `channels`, see `Channels::set_rule`.
+///
+/// # Arguments
+///
+/// * `channels`: The channels shared with the other tasks.
+/// * `settings`: How the game runs.
+/// * `seed`: The seed of the random number generator randomizing the board.
+/// * `interval`: A `Duration` value representing the time interval between updates.
+pub async fn refresh_gol_board<
+    const W: usize,
+    const H: usize,
+    const N: usize,
+    const HASH_HISTORY_LEN: usize,
+>(
+    channels: &Channels<N>,
+    settings: &GameSettings,
+    seed: u32,
+    interval: Duration,
+) {
+    // Create Game of life boardgame
+    let mut rule = channels.rule();
+    info!("GOL rule: {}", Display(&rule));
+    info!("GOL seed: {=u32:#x}", seed);
+    debug!("randomize with probability_to_live = {}", STARTUP_DENSITY);
+    let mut game = Game::<W, H, HASH_HISTORY_LEN>::new(settings, seed);
+    display(game.board(), true);
+    dump(game.board(), &rule);
+
+    channels.game_grid.signal(game.generation());
+    Timer::after(interval).await;
+
+    loop {
+        let active_rule = channels.rule();
+        if active_rule != rule {
+            rule = active_rule;
+            info!("GOL rule switched to {}", Display(&rule));
+        }
+
+        match game.step(&rule) {
+            (outcome, Stagnation::Live) => info!("GOL board updated! {}", outcome),
+            (outcome, _) if !outcome.changed => info!("GOL did not evolve: {}", outcome),
+            (outcome, verdict) => info!("GOL board is stagnating: {} {}", verdict, outcome),
+        }
+        if game.should_reseed() {
+            display(game.board(), true);
+            info!(
+                "GOL stagnated for {} repeats... Randomize it again :)",
+                game.repeats()
+            );
+            debug!("randomize with probability_to_live = {}", RESEED_DENSITY);
+            game.reseed();
+            dump(game.board(), &rule);
+        }
+
+        display(game.board(), false);
+        channels.game_grid.signal(game.generation());
+        Timer::after(interval).await;
+    }
+}
+
+/// Displays the game grid through defmt.
+///
+/// # Arguments
+///
+/// * `gg`: The game grid to display.
+/// * `display_neighboor`: If set to `true`, displays the number of alive neighbors of each cell.
+fn display<const W: usize, const H: usize>(gg: &GameGrid<W, H>, display_neighboor: bool) {
+    (0..H).for_each(|y| {
+        let tmp: [u8; W] = gg.row(y).map(|v| if v { 1 } else { 0 });
+        if display_neighboor {
+            let mut tmp_ngh: [u8; W] = [0; W];
+            (0..W).for_each(|x| {
+                tmp_ngh[x] = gg.count_alive_neighbors(x, y);
+            });
+            debug!("{}| NGHB :{}|", tmp, tmp_ngh);
+        } else {
+            debug!("{}|", tmp);
+        }
+    });
+    debug!("HASH:{}", gg.get_hash())
+}
+
+/// Logs the game grid as an RLE pattern, which can be pasted as is into other Life tools (e.g. Golly).
+///
+/// # Arguments
+///
+/// * `gg`: The game grid to dump.
+/// * `rule`: The rule written in the pattern header.
+fn dump<const W: usize, const H: usize>(gg: &GameGrid<W, H>, rule: &Rule) {
+    let mut text = TextBuffer::<DUMP_BUFFER_LEN>::new();
+    match write_rle(gg, rule, &mut text) {
+        Ok(()) => info!("GOL board:\n{=str}", text.as_str()),
+        Err(_) => warn!("GOL board does not fit in {} bytes of RLE", DUMP_BUFFER_LEN),
+    }
+}
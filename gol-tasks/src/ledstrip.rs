@@ -0,0 +1,71 @@
+use cichlid::ColorRGB;
+use gol_core::layout::LedLayout;
+use gol_core::output::{Gamma, OutputStage};
+use gol_core::power::PowerModel;
+
+use crate::channels::Channels;
+use crate::sink::LedSink;
+
+/// How `refresh_ledstrip` lights the ledstrip.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct OutputSettings<L> {
+    /// How the cells of the game grid are wired on the ledstrip.
+    pub layout: L,
+    /// The color of the LEDs of the ledstrip showing no cell.
+    pub padding_color: ColorRGB,
+    /// The gamma correction of each channel of the LEDs.
+    pub gamma: Gamma,
+    /// If set to `true`, the colors are temporally dithered so dim fades stay smooth.
+    pub dithering: bool,
+    /// The current drawn by the LEDs and the limit of the supply, frames drawing more are dimmed.
+    pub power_model: Option<PowerModel>,
+}
+
+/// Refreshes the LED strip with the latest color values sent by `animate_ledstrip`.
+///
+/// This function continuously waits for new color values. Once a new set of color values is received, the function
+/// reorders them along `OutputSettings::layout` and lights up the whole LED strip, LEDs showing no cell being set
+/// to `OutputSettings::padding_color`.
+/// The colors then go through the output stage: the gamma, the brightness of `channels` (see
+/// `Channels::set_brightness`) and the dithering. Frames drawing more current than the power model allows are dimmed,
+/// which is logged each time the limiter engages.
+///
+/// # Arguments
+///
+/// * `channels`: The channels shared with the other tasks.
+/// * `settings`: How the ledstrip is lit.
+/// * `sink`: The ledstrip of `LEDS` LEDs, e.g. a `Ws2812`.
+pub async fn refresh_ledstrip<S, L, const N: usize, const LEDS: usize>(
+    channels: &Channels<N>,
+    settings: &OutputSettings<L>,
+    mut sink: S,
+) where
+    S: LedSink<LEDS>,
+    L: LedLayout,
+{
+    let mut leds = [settings.padding_color; LEDS];
+    let mut frame = [ColorRGB::Black; LEDS];
+    let mut output =
+        OutputStage::<LEDS>::new(settings.gamma, channels.brightness(), settings.dithering);
+    output.set_power_model(settings.power_model);
+    let mut limited = false;
+    // Loop forever making RGB values and pushing them out to the ledstrip.
+    loop {
+        // light ledstrip with received value
+        let colors = channels.led_strip.wait().await.led_strip_update;
+        settings.layout.arrange(&colors, &mut leds);
+        output.set_brightness(channels.brightness());
+        if let Some(estimate) = output.process(&leds, &mut frame) {
+            if estimate.is_limited() && !limited {
+                warn!(
+                    "Power limiter engaged: {} mA requested, dimmed to {} mA",
+                    estimate.requested_ma, estimate.drawn_ma
+                );
+            } else if !estimate.is_limited() && limited {
+                info!("Power limiter released: {} mA", estimate.drawn_ma);
+            }
+            limited = estimate.is_limited();
+        }
+        sink.write(&frame).await;
+    }
+}
@@ -0,0 +1,281 @@
+//! The embassy tasks of pico-game-of-light: the game, the ledstrip animation and the ledstrip output.
+//!
+//! The tasks only rely on `embassy-sync` and `embassy-time`, and write their frames to any `LedSink`. The firmware
+//! runs them on the RP2040 with the `Ws2812` driver, and they run as well on the host, writing to a `RecordingSink`
+//! on a mock clock, where they are tested end to end with `cargo test-host`.
+#![cfg_attr(not(any(test, feature = "std")), no_std)]
+#![cfg_attr(test, feature(type_alias_impl_trait))]
+
+mod fmt;
+
+pub mod animation;
+pub mod channels;
+pub mod game;
+pub mod ledstrip;
+pub mod sink;
+pub mod smart_leds;
+
+#[cfg(test)]
+mod mock_time;
+
+#[cfg(test)]
+mod tests {
+    use cichlid::ColorRGB;
+    use embassy_time::Duration;
+    use gol_core::color_space::ColorSpace;
+    use gol_core::easing::Easing;
+    use gol_core::frame::{FrameStep, FrameStyle, Generation};
+    use gol_core::game::{Game, GameSettings};
+    use gol_core::idle::IdleAnimation;
+    use gol_core::layout::{LedLayout, Padded, Serpentine, StripPadding};
+    use gol_core::ledstrip_effect::{TransitionColorSpaces, TransitionEasing};
+    use gol_core::output::{Gamma, OutputStage};
+    use gol_core::palette::{CrossFade, Palette};
+    use gol_core::rule::Rule;
+    use gol_core::stagnation::ReseedPolicy;
+    use gol_core::timing::{TransitionClock, TransitionTiming};
+    use gol_core::topology::Topology;
+
+    use crate::animation::{animate_ledstrip, AnimationSettings};
+    use crate::channels::Channels;
+    use crate::game::refresh_gol_board;
+    use crate::ledstrip::{refresh_ledstrip, OutputSettings};
+    use crate::mock_time::TestExecutor;
+    use crate::sink::RecordingSink;
+
+    const GRID_WIDTH: usize = 17;
+    const GRID_HEIGHT: usize = 8;
+    const NUM_LEDS: usize = GRID_WIDTH * GRID_HEIGHT;
+    const LEDSTRIP_LENGTH: usize = 144;
+    const SEED: u32 = 0x1234;
+    const INTERVAL: Duration = Duration::from_millis(1000);
+
+    const CONWAY: Rule = match Rule::parse("B3/S23") {
+        Ok(rule) => rule,
+        Err(_) => panic!(),
+    };
+
+    const SEEDS: Rule = match Rule::parse("B2/S") {
+        Ok(rule) => rule,
+        Err(_) => panic!(),
+    };
+
+    const GAME: GameSettings = GameSettings {
+        topology: Topology::Torus,
+        reseed_policy: ReseedPolicy {
+            still_life_repeats: 1,
+            oscillator_repeats: 3,
+        },
+    };
+
+    const STYLE: FrameStyle = FrameStyle {
+        width: GRID_WIDTH,
+        age_coloring: true,
+        easing: TransitionEasing {
+            birth: Easing::QuadIn,
+            death: Easing::QuadOut,
+            steady: Easing::Linear,
+        },
+        color_spaces: TransitionColorSpaces {
+            birth: ColorSpace::Hsv,
+            death: ColorSpace::Hsv,
+            steady: ColorSpace::Rgb,
+        },
+        idle: IdleAnimation::NONE,
+    };
+
+    /// Transitions last 200 ms, with a frame every 20 ms.
+    const ANIMATION: AnimationSettings = AnimationSettings {
+        style: STYLE,
+        timing: TransitionTiming::percent(20),
+        palette_fade_steps: 1,
+        frame_delay: Duration::from_millis(20),
+    };
+
+    const OUTPUT: OutputSettings<Padded<Serpentine>> = OutputSettings {
+        layout: Padded::new(
+            Serpentine::new(GRID_WIDTH, GRID_HEIGHT),
+            StripPadding::new(LEDSTRIP_LENGTH),
+        ),
+        padding_color: ColorRGB::Black,
+        gamma: Gamma::SRGB,
+        dithering: false,
+        power_model: None,
+    };
+
+    // One task of each per test, as each test spawns them on its own executor.
+    #[embassy_executor::task(pool_size = 2)]
+    async fn game(channels: &'static Channels<NUM_LEDS>) {
+        refresh_gol_board::<GRID_WIDTH, GRID_HEIGHT, NUM_LEDS, 32>(channels, &GAME, SEED, INTERVAL)
+            .await
+    }
+
+    #[embassy_executor::task(pool_size = 2)]
+    async fn animation(
+        channels: &'static Channels<NUM_LEDS>,
+        settings: &'static AnimationSettings,
+    ) {
+        animate_ledstrip(channels, settings, INTERVAL).await
+    }
+
+    #[embassy_executor::task(pool_size = 2)]
+    async fn output(channels: &'static Channels<NUM_LEDS>, sink: RecordingSink<LEDSTRIP_LENGTH>) {
+        refresh_ledstrip(channels, &OUTPUT, sink).await
+    }
+
+    /// Spawns the tasks of a test on a mock clock, with channels of their own.
+    ///
+    /// The channels are leaked, as the tasks borrow them for as long as they run, i.e. forever.
+    fn spawn_tasks(
+        animation_settings: &'static AnimationSettings,
+        sink: RecordingSink<LEDSTRIP_LENGTH>,
+    ) -> (TestExecutor, &'static Channels<NUM_LEDS>) {
+        let executor = TestExecutor::new();
+        let channels = Box::leak(Box::new(Channels::new(CONWAY, Palette::CLASSIC, u8::MAX)));
+        let spawner = executor.spawner();
+        spawner.spawn(game(channels)).unwrap();
+        spawner
+            .spawn(animation(channels, animation_settings))
+            .unwrap();
+        spawner.spawn(output(channels, sink)).unwrap();
+        executor.run_until_idle();
+        (executor, channels)
+    }
+
+    /// Computes the generations of the board the way `refresh_gol_board` does, as long as it does not stagnate.
+    ///
+    /// The first generation is the random board, each next one applies the next rule of `rules`.
+    fn generations(rules: &[Rule]) -> Vec<Generation<NUM_LEDS>> {
+        let mut game = Game::<GRID_WIDTH, GRID_HEIGHT, 32>::new(&GAME, SEED);
+        let mut generations = vec![game.generation()];
+        rules.iter().for_each(|rule| {
+            game.step(rule);
+            generations.push(game.generation());
+        });
+        generations
+    }
+
+    /// Computes the frame shown `elapsed` after the transition from `prev` to `next` started.
+    fn frame(
+        prev: &Generation<NUM_LEDS>,
+        next: &Generation<NUM_LEDS>,
+        palette: Palette<'static>,
+        brightness: u8,
+        elapsed: Duration,
+    ) -> [ColorRGB; LEDSTRIP_LENGTH] {
+        let mut clock = TransitionClock::new(ANIMATION.timing);
+        clock.start(0, INTERVAL.as_micros());
+        let fade = CrossFade::new(palette, 1);
+        let step = FrameStep {
+            progress: clock.progress(elapsed.as_micros()),
+            tick: 0,
+            fade: &fade,
+        };
+        let mut leds = [OUTPUT.padding_color; LEDSTRIP_LENGTH];
+        OUTPUT
+            .layout
+            .arrange(&STYLE.render_frame(prev, next, &step), &mut leds);
+        let mut frame = [ColorRGB::Black; LEDSTRIP_LENGTH];
+        OutputStage::new(OUTPUT.gamma, brightness, OUTPUT.dithering).process(&leds, &mut frame);
+        frame
+    }
+
+    /// Computes the frame shown once the transition from `prev` to `next` is done.
+    fn settled(
+        prev: &Generation<NUM_LEDS>,
+        next: &Generation<NUM_LEDS>,
+        palette: Palette<'static>,
+        brightness: u8,
+    ) -> [ColorRGB; LEDSTRIP_LENGTH] {
+        frame(prev, next, palette, brightness, INTERVAL)
+    }
+
+    #[test]
+    fn tasks_run_end_to_end() {
+        let sink = RecordingSink::new();
+        let (executor, channels) = spawn_tasks(&ANIMATION, sink.clone());
+        let generations = generations(&[CONWAY, CONWAY, SEEDS]);
+        assert_ne!(generations[3], self::generations(&[CONWAY; 3])[3]);
+        let empty = Generation::EMPTY;
+        let ms = Duration::from_millis;
+
+        // The first generation fades in over 200 ms, a frame every 20 ms.
+        assert_eq!(sink.len(), 1);
+        assert!(sink.frames()[0].iter().all(|&c| c == ColorRGB::Black));
+        executor.advance(ms(100));
+        assert_eq!(sink.len(), 6);
+        let halfway = frame(&empty, &generations[0], Palette::CLASSIC, u8::MAX, ms(100));
+        assert_eq!(sink.last(), Some(halfway));
+        assert_ne!(halfway, sink.frames()[0]);
+        executor.advance(ms(100));
+        let first = settled(&empty, &generations[0], Palette::CLASSIC, u8::MAX);
+        assert_eq!(sink.len(), 11);
+        assert_eq!(sink.last(), Some(first));
+
+        // Then the ledstrip stays still until the next generation, which comes after the interval.
+        executor.advance(ms(799));
+        assert_eq!(sink.len(), 11);
+        executor.advance(ms(201));
+        let second = settled(&generations[0], &generations[1], Palette::CLASSIC, u8::MAX);
+        assert_eq!(sink.len(), 22);
+        assert_eq!(sink.last(), Some(second));
+
+        // The brightness set by another task applies to the next frames.
+        channels.set_brightness(64);
+        executor.advance(ms(1000));
+        let third = settled(&generations[1], &generations[2], Palette::CLASSIC, 64);
+        assert_eq!(sink.last(), Some(third));
+
+        // So do the rule and the palette, from the next generation on.
+        channels.set_rule(SEEDS);
+        channels.set_palette(Palette::MONOCHROME);
+        executor.advance(ms(1000));
+        let fourth = settled(&generations[2], &generations[3], Palette::MONOCHROME, 64);
+        assert_eq!(sink.last(), Some(fourth));
+        assert!(sink
+            .frames()
+            .iter()
+            .all(|frame| frame[NUM_LEDS..].iter().all(|&c| c == ColorRGB::Black)));
+    }
+
+    #[test]
+    fn palettes_are_cross_faded_in_between_generations() {
+        /// Palettes are cross-faded in over 5 frames.
+        static FADING: AnimationSettings = AnimationSettings {
+            palette_fade_steps: 5,
+            ..ANIMATION
+        };
+        let sink = RecordingSink::new();
+        let (executor, channels) = spawn_tasks(&FADING, sink.clone());
+        let generations = generations(&[CONWAY]);
+        let ms = Duration::from_millis;
+        let classic = settled(&generations[0], &generations[1], Palette::CLASSIC, u8::MAX);
+        let monochrome = settled(
+            &generations[0],
+            &generations[1],
+            Palette::MONOCHROME,
+            u8::MAX,
+        );
+        // Newborns flash white in both palettes, the cells that survived differ.
+        assert_ne!(classic, monochrome);
+
+        // The second generation is settled and the style has no idle animation, so the ledstrip is still.
+        executor.advance(ms(1300));
+        let frames = sink.len();
+        assert_eq!(sink.last(), Some(classic));
+
+        // A new palette is cross-faded in at once, frame after frame, then the ledstrip is still again.
+        channels.set_palette(Palette::MONOCHROME);
+        executor.run_until_idle();
+        assert_eq!(sink.len(), frames + 1);
+        assert_eq!(sink.last(), Some(classic));
+        executor.advance(ms(100));
+        assert_eq!(sink.len(), frames + 6);
+        assert!(sink.frames()[frames + 1..frames + 5]
+            .iter()
+            .all(|frame| *frame != classic && *frame != monochrome));
+        assert_eq!(sink.last(), Some(monochrome));
+        executor.advance(ms(500));
+        assert_eq!(sink.len(), frames + 6);
+    }
+}
@@ -0,0 +1,151 @@
+//! Mock time for the host tests: the clock of embassy-time only moves when the test advances it, and the tasks run on
+//! an executor the test polls itself, so every frame is checked at an exact instant.
+//!
+//! The std executor of embassy runs its tasks on a thread of their own and never returns, so a test could not tell when
+//! they are done with an instant before moving the clock. `TestExecutor` polls the same raw executor on the test thread
+//! instead, until no task is ready.
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Mutex, MutexGuard};
+
+use embassy_executor::raw;
+use embassy_executor::Spawner;
+use embassy_time::driver::{AlarmHandle, Driver};
+use embassy_time::{Duration, Instant};
+
+/// A time driver whose clock only moves in `TestExecutor::advance`.
+struct MockDriver {
+    /// The current time, in ticks.
+    now: AtomicU64,
+    alarms: Mutex<Vec<Alarm>>,
+}
+
+/// The alarm of an executor, which wakes it when a timer expires.
+struct Alarm {
+    /// The time the callback is called at, in ticks.
+    at: u64,
+    /// The callback and its context, as an address since raw pointers are not `Send`.
+    callback: Option<(fn(*mut ()), usize)>,
+}
+
+embassy_time::time_driver_impl!(static DRIVER: MockDriver = MockDriver {
+    now: AtomicU64::new(0),
+    alarms: Mutex::new(Vec::new()),
+});
+
+impl Driver for MockDriver {
+    fn now(&self) -> u64 {
+        self.now.load(Ordering::SeqCst)
+    }
+
+    unsafe fn allocate_alarm(&self) -> Option<AlarmHandle> {
+        let mut alarms = self.alarms.lock().unwrap();
+        alarms.push(Alarm {
+            at: u64::MAX,
+            callback: None,
+        });
+        Some(AlarmHandle::new((alarms.len() - 1) as u8))
+    }
+
+    fn set_alarm_callback(&self, alarm: AlarmHandle, callback: fn(*mut ()), ctx: *mut ()) {
+        self.alarms.lock().unwrap()[alarm.id() as usize].callback = Some((callback, ctx as usize));
+    }
+
+    fn set_alarm(&self, alarm: AlarmHandle, timestamp: u64) {
+        self.alarms.lock().unwrap()[alarm.id() as usize].at = timestamp;
+        // An alarm in the past fires at once.
+        self.fire();
+    }
+}
+
+impl MockDriver {
+    /// Calls the callbacks of the alarms that are due.
+    fn fire(&self) {
+        let now = self.now();
+        let due: Vec<(fn(*mut ()), usize)> = self
+            .alarms
+            .lock()
+            .unwrap()
+            .iter_mut()
+            .filter(|alarm| alarm.at <= now)
+            .filter_map(|alarm| {
+                alarm.at = u64::MAX;
+                alarm.callback
+            })
+            .collect();
+        due.into_iter()
+            .for_each(|(callback, ctx)| callback(ctx as *mut ()));
+    }
+
+    /// Returns the time of the next alarm, in ticks.
+    fn next_alarm(&self) -> u64 {
+        let alarms = self.alarms.lock().unwrap();
+        alarms
+            .iter()
+            .map(|alarm| alarm.at)
+            .min()
+            .unwrap_or(u64::MAX)
+    }
+}
+
+/// The clock is shared by the whole test binary, so the tests running tasks take turns.
+static CLOCK: Mutex<()> = Mutex::new(());
+
+/// An executor polled by the test, at the instants it advances the mock clock to.
+pub(crate) struct TestExecutor {
+    executor: &'static raw::Executor,
+    /// Set when a task is ready to be polled.
+    pending: &'static AtomicBool,
+    _clock: MutexGuard<'static, ()>,
+}
+
+impl TestExecutor {
+    /// Creates an executor, waiting for the tests running before to release the clock.
+    pub(crate) fn new() -> Self {
+        // A failed test poisons the lock, but leaves the clock usable.
+        let clock = CLOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let pending: &'static AtomicBool = Box::leak(Box::new(AtomicBool::new(true)));
+        let executor = Box::leak(Box::new(raw::Executor::new(
+            pend,
+            pending as *const AtomicBool as *mut (),
+        )));
+        TestExecutor {
+            executor,
+            pending,
+            _clock: clock,
+        }
+    }
+
+    /// Returns the spawner of the executor.
+    pub(crate) fn spawner(&self) -> Spawner {
+        self.executor.spawner()
+    }
+
+    /// Polls the tasks until every one of them waits for a signal or a timer.
+    pub(crate) fn run_until_idle(&self) {
+        while self.pending.swap(false, Ordering::SeqCst) {
+            // SAFETY: the executor is only polled from the thread holding the clock.
+            unsafe { self.executor.poll() };
+        }
+    }
+
+    /// Moves the clock forward by `duration`, stopping at each timer that expires meanwhile to run the tasks.
+    pub(crate) fn advance(&self, duration: Duration) {
+        let end = Instant::now().as_ticks() + duration.as_ticks();
+        self.run_until_idle();
+        loop {
+            let next = DRIVER.next_alarm().min(end).max(DRIVER.now());
+            DRIVER.now.store(next, Ordering::SeqCst);
+            DRIVER.fire();
+            self.run_until_idle();
+            if next == end {
+                break;
+            }
+        }
+    }
+}
+
+/// Marks the executor of `ctx` as having a task ready to be polled.
+fn pend(ctx: *mut ()) {
+    // SAFETY: `ctx` is the leaked flag of a `TestExecutor`.
+    unsafe { &*(ctx as *const AtomicBool) }.store(true, Ordering::SeqCst);
+}
@@ -0,0 +1,130 @@
+use core::future::Future;
+
+use cichlid::ColorRGB;
+
+/// A ledstrip of `N` LEDs the frames are written to, e.g. the `Ws2812` driver of the firmware.
+///
+/// Writing is asynchronous, so the other tasks keep running while a frame is clocked out.
+pub trait LedSink<const N: usize> {
+    /// The future returned by `write`.
+    type WriteFuture<'a>: Future<Output = ()> + 'a
+    where
+        Self: 'a;
+
+    /// Writes the color of every LED of the strip.
+    fn write<'a>(&'a mut self, colors: &'a [ColorRGB; N]) -> Self::WriteFuture<'a>;
+}
+
+#[cfg(any(test, feature = "std"))]
+pub use recording::RecordingSink;
+
+#[cfg(any(test, feature = "std"))]
+mod recording {
+    use core::future::{ready, Ready};
+    use std::sync::{Arc, Mutex};
+    use std::vec::Vec;
+
+    use cichlid::ColorRGB;
+
+    use super::LedSink;
+    use crate::smart_leds::{fill_frame, SmartLedsWriteAsync, RGB8};
+
+    /// A ledstrip keeping in memory every frame written to it, to check the output of the tasks on the host.
+    ///
+    /// Clones share the same frames, so a clone can be handed to `refresh_ledstrip` and the other one inspected.
+    #[derive(Clone, Default)]
+    pub struct RecordingSink<const N: usize> {
+        frames: Arc<Mutex<Vec<[ColorRGB; N]>>>,
+    }
+
+    impl<const N: usize> RecordingSink<N> {
+        /// Creates a sink without any frame.
+        pub fn new() -> Self {
+            RecordingSink {
+                frames: Arc::new(Mutex::new(Vec::new())),
+            }
+        }
+
+        /// Returns the frames written so far, oldest first.
+        pub fn frames(&self) -> Vec<[ColorRGB; N]> {
+            self.frames.lock().unwrap().clone()
+        }
+
+        /// Returns the number of frames written so far.
+        pub fn len(&self) -> usize {
+            self.frames.lock().unwrap().len()
+        }
+
+        /// Returns `true` if no frame was written yet.
+        pub fn is_empty(&self) -> bool {
+            self.len() == 0
+        }
+
+        /// Returns the last frame written, i.e. what the ledstrip shows.
+        pub fn last(&self) -> Option<[ColorRGB; N]> {
+            self.frames.lock().unwrap().last().copied()
+        }
+    }
+
+    impl<const N: usize> LedSink<N> for RecordingSink<N> {
+        type WriteFuture<'a> = Ready<()>;
+
+        fn write<'a>(&'a mut self, colors: &'a [ColorRGB; N]) -> Self::WriteFuture<'a> {
+            self.frames.lock().unwrap().push(*colors);
+            ready(())
+        }
+    }
+
+    impl<const N: usize> SmartLedsWriteAsync for RecordingSink<N> {
+        type Error = core::convert::Infallible;
+        type Color = RGB8;
+        type WriteFuture<'a> = Ready<Result<(), Self::Error>>;
+
+        fn write<'a, T, I>(&'a mut self, iterator: T) -> Self::WriteFuture<'a>
+        where
+            T: IntoIterator<Item = I>,
+            I: Into<Self::Color>,
+        {
+            let mut frame = [ColorRGB::Black; N];
+            fill_frame(&mut frame, iterator);
+            self.frames.lock().unwrap().push(frame);
+            ready(Ok(()))
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn clones_share_the_frames() {
+            let sink = RecordingSink::<2>::new();
+            let mut writer = sink.clone();
+            assert!(sink.is_empty());
+            drop(LedSink::write(
+                &mut writer,
+                &[ColorRGB::White, ColorRGB::Black],
+            ));
+            drop(LedSink::write(&mut writer, &[ColorRGB::Black; 2]));
+            assert_eq!(sink.len(), 2);
+            assert_eq!(sink.frames()[0], [ColorRGB::White, ColorRGB::Black]);
+            assert_eq!(sink.last(), Some([ColorRGB::Black; 2]));
+        }
+
+        #[cfg(feature = "smart-leds")]
+        #[test]
+        fn blocking_writes_smart_leds_iterators() {
+            use smart_leds_trait::SmartLedsWrite;
+
+            use crate::smart_leds::Blocking;
+
+            let sink = RecordingSink::<2>::new();
+            let mut writer = Blocking(sink.clone());
+            writer.write([RGB8::new(255, 0, 0)].into_iter()).unwrap();
+            assert_eq!(
+                sink.last(),
+                Some([ColorRGB { r: 255, g: 0, b: 0 }, ColorRGB::Black])
+            );
+        }
+    }
+}
@@ -0,0 +1,115 @@
+//! Interoperability with the smart-leds ecosystem.
+//!
+//! `SmartLedsWriteAsync` is the asynchronous counterpart of `smart_leds_trait::SmartLedsWrite`: it takes the same
+//! iterators of `RGB8` colors, e.g. the `brightness`, `gamma` or `hsv2rgb` adapters of the `smart-leds` crate, but
+//! the colors are clocked out without blocking the other tasks. The firmware `Ws2812` driver implements it.
+//!
+//! With the `smart-leds` feature, `Blocking` turns any `SmartLedsWriteAsync` into a `SmartLedsWrite`, for the crates
+//! of the ecosystem that only know the blocking trait.
+use core::future::Future;
+
+use cichlid::ColorRGB;
+pub use rgb::RGB8;
+
+/// An asynchronous ledstrip the colors of an iterator are written to, see `smart_leds_trait::SmartLedsWrite`.
+pub trait SmartLedsWriteAsync {
+    /// The error returned when the colors can not be written.
+    type Error;
+    /// The color of a LED, `RGB8` for WS2812 strips.
+    type Color;
+    /// The future returned by `write`.
+    type WriteFuture<'a>: Future<Output = Result<(), Self::Error>> + 'a
+    where
+        Self: 'a;
+
+    /// Writes the colors of `iterator` to the ledstrip, starting from its first LED.
+    ///
+    /// The iterator is consumed before this function returns, so the future does not borrow it: the colors are
+    /// clocked out when the future is polled.
+    fn write<'a, T, I>(&'a mut self, iterator: T) -> Self::WriteFuture<'a>
+    where
+        T: IntoIterator<Item = I>,
+        I: Into<Self::Color>;
+}
+
+/// Fills a frame of `N` LEDs with the colors of an iterator.
+///
+/// The LEDs past the end of the iterator are switched off, and the colors past the end of the ledstrip are ignored,
+/// so the whole strip can always be clocked out and no LED keeps a stale color.
+///
+/// # Arguments
+///
+/// * `frame`: The colors of the LEDs of the ledstrip.
+/// * `iterator`: The colors to show, starting from the first LED.
+pub fn fill_frame<const N: usize, T, I>(frame: &mut [ColorRGB; N], iterator: T)
+where
+    T: IntoIterator<Item = I>,
+    I: Into<RGB8>,
+{
+    let mut colors = iterator.into_iter();
+    frame.iter_mut().for_each(|led| {
+        *led = colors.next().map_or(ColorRGB::Black, |color| {
+            let RGB8 { r, g, b } = color.into();
+            ColorRGB { r, g, b }
+        })
+    });
+}
+
+#[cfg(feature = "smart-leds")]
+pub use blocking::Blocking;
+
+#[cfg(feature = "smart-leds")]
+mod blocking {
+    use smart_leds_trait::SmartLedsWrite;
+
+    use super::SmartLedsWriteAsync;
+
+    /// Turns a `SmartLedsWriteAsync` into a blocking `SmartLedsWrite`.
+    ///
+    /// `write` waits in place for the colors to be clocked out. When the ledstrip is slower than the renderer, every
+    /// other task of the executor stalls meanwhile: on the RP2040, render on the second core to keep them running.
+    #[derive(Clone, Debug, Default, PartialEq, Eq)]
+    pub struct Blocking<W>(pub W);
+
+    impl<W> Blocking<W> {
+        /// Returns the wrapped ledstrip.
+        pub fn into_inner(self) -> W {
+            self.0
+        }
+    }
+
+    impl<W: SmartLedsWriteAsync> SmartLedsWrite for Blocking<W> {
+        type Error = W::Error;
+        type Color = W::Color;
+
+        fn write<T, I>(&mut self, iterator: T) -> Result<(), Self::Error>
+        where
+            T: Iterator<Item = I>,
+            I: Into<Self::Color>,
+        {
+            embassy_futures::block_on(self.0.write(iterator))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fill_frame_pads_and_truncates() {
+        let mut frame = [ColorRGB::White; 3];
+        fill_frame(&mut frame, [RGB8::new(1, 2, 3)]);
+        assert_eq!(
+            frame,
+            [
+                ColorRGB { r: 1, g: 2, b: 3 },
+                ColorRGB::Black,
+                ColorRGB::Black
+            ]
+        );
+
+        fill_frame(&mut frame, (0..5).map(|v| (v, v, v)));
+        assert_eq!(frame[2], ColorRGB { r: 2, g: 2, b: 2 });
+    }
+}
@@ -4,35 +4,70 @@
 
 use defmt::*;
 use embassy_executor::Spawner;
+use embassy_rp::clocks::RoscRng;
 use embassy_rp::gpio::Pin;
 use embassy_rp::pio::{PioInstanceBase, PioPeripheral, SmInstanceBase};
-use embassy_time::{Duration, Timer};
+use embassy_time::Duration;
+use rand_core::RngCore;
 use {defmt_rtt as _, panic_probe as _};
 
-mod game_grid;
-mod ledstrip_effect;
 mod ws2812;
 
-use cichlid::ColorRGB;
-
-use crate::ws2812::{Ws2812, NUM_LEDS};
-use ledstrip_effect::{LedstripColors, TRANSITION_STEPS};
-
-use embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex;
-use embassy_sync::signal::Signal;
-
-struct GameGridMessage {
-    game_grid_update: [bool; NUM_LEDS],
-}
-
-struct LedStripMessage {
-    led_strip_update: [ColorRGB; NUM_LEDS],
-}
-
-static GG_SIGNAL: Signal<CriticalSectionRawMutex, GameGridMessage> = Signal::new();
-static LED_SIGNAL: Signal<CriticalSectionRawMutex, LedStripMessage> = Signal::new();
-
-pub(crate) const LEDSTRIP_REFRESH_DELAY: Duration = Duration::from_millis(20);
+use gol_core::config::*;
+use gol_core::layout::{Padded, Serpentine};
+use gol_core::ledstrip_effect::MAX_BRIGHTNESS;
+use gol_core::output::Gamma;
+use gol_core::power::PowerModel;
+use gol_tasks::animation::{self, AnimationSettings};
+use gol_tasks::channels::Channels;
+use gol_tasks::game;
+use gol_tasks::ledstrip::{self, OutputSettings};
+
+use crate::ws2812::Ws2812;
+
+// The board, the game and the animation are configured in `gol_core::config`, shared with the simulator.
+
+/// The interval between two generations of the game grid.
+const GENERATION_INTERVAL: Duration = Duration::from_micros(GENERATION_INTERVAL_US);
+
+/// The interval between two frames of the ledstrip.
+const LEDSTRIP_REFRESH_DELAY: Duration = Duration::from_micros(FRAME_INTERVAL_US);
+
+/// The gamma correction of each channel of the LEDs, see `Gamma`.
+const OUTPUT_GAMMA: Gamma = Gamma::SRGB;
+
+/// If set to `true`, the colors sent to the ledstrip are temporally dithered so dim fades stay smooth.
+/// It relies on frames being sent continuously, i.e. on an `IDLE_ANIMATION`.
+const OUTPUT_DITHERING: bool = true;
+
+/// The current drawn by the LEDs and the limit of the supply, frames drawing more are dimmed.
+/// `PowerModel::WS2812B` is powered by a 500 mA USB port.
+const POWER_MODEL: PowerModel = PowerModel::WS2812B;
+
+/// The seed of the board randomization. `None` draws a new one from the ring oscillator at each boot,
+/// set it to the seed logged by a previous run to replay it.
+const FIXED_SEED: Option<u32> = None;
+
+/// How `animate_ledstrip` draws the frames.
+const ANIMATION_SETTINGS: AnimationSettings = AnimationSettings {
+    style: FRAME_STYLE,
+    timing: TRANSITION_TIMING,
+    palette_fade_steps: PALETTE_FADE_STEPS,
+    frame_delay: LEDSTRIP_REFRESH_DELAY,
+};
+
+/// How `refresh_ledstrip` lights the ledstrip.
+const OUTPUT_SETTINGS: OutputSettings<Padded<Serpentine>> = OutputSettings {
+    layout: LED_LAYOUT,
+    padding_color: PADDING_COLOR,
+    gamma: OUTPUT_GAMMA,
+    dithering: OUTPUT_DITHERING,
+    power_model: Some(POWER_MODEL),
+};
+
+/// The signals between the tasks, and the rule, palette and brightness they use.
+/// They can be switched while the tasks run, see `Channels::set_rule`, `set_palette` and `set_brightness`.
+static CHANNELS: Channels<NUM_LEDS> = Channels::new(STARTUP_RULE, STARTUP_PALETTE, MAX_BRIGHTNESS);
 
 #[embassy_executor::main]
 async fn main(spawner: Spawner) {
@@ -46,126 +81,45 @@ async fn main(spawner: Spawner) {
 
     // ---- ----"LOOP"---- ---- ----
     // ---- ---- ---- ---- ---- ----
-    unwrap!(spawner.spawn(refresh_gol_board(Duration::from_millis(5000))));
-    unwrap!(spawner.spawn(animate_ledstrip()));
+    unwrap!(spawner.spawn(refresh_gol_board(GENERATION_INTERVAL)));
+    unwrap!(spawner.spawn(animate_ledstrip(GENERATION_INTERVAL)));
     unwrap!(spawner.spawn(refresh_ledstrip(ws2812)));
 }
 
-/// Updates a Game of Life board at a given interval and signals the changes to the embassy_sync signal `GG_SIGNAL` as a `GameGridMessage`.
-/// This function creates a new Game of Life boardgame and randomizes it with a 42% chance of each cell being alive.
-/// If the boardgame does not evolve after an update, the board is randomized again.
+/// Runs the game, see `gol_tasks::game::refresh_gol_board`.
+/// The random generator seed is drawn from the ring oscillator, see `FIXED_SEED` to replay a run.
 ///
 /// # Arguments
 ///
 /// * `interval`: A `Duration` value representing the time interval between updates.
-///
 #[embassy_executor::task]
 async fn refresh_gol_board(interval: Duration) {
-    // Create Game of life boardgame
-    let mut gg: game_grid::GameGrid = Default::default();
-    gg.randomize(0.42);
-    gg.display(true);
-
-    GG_SIGNAL.signal(GameGridMessage {
-        game_grid_update: gg.to_bool_arrray(),
-    });
-    Timer::after(interval).await;
-
-    loop {
-        if !gg.update() {
-            info!("GOL board updated!");
-        } else {
-            gg.display(true);
-            info!("GOL did not evolve... Randomize it again :)");
-            // TODO: store hash and detect cycle through
-            gg.randomize(0.3);
-        }
-
-        gg.display(false);
-        GG_SIGNAL.signal(GameGridMessage {
-            game_grid_update: gg.to_bool_arrray(),
-        });
-        Timer::after(interval).await;
-    }
+    let seed = FIXED_SEED.unwrap_or_else(|| RoscRng.next_u32());
+    game::refresh_gol_board::<GRID_WIDTH, GRID_HEIGHT, NUM_LEDS, HASH_HISTORY_LEN>(
+        &CHANNELS,
+        &GAME_SETTINGS,
+        seed,
+        interval,
+    )
+    .await
 }
 
-/// Animates a LED strip based on updates to a game grid.
+/// Animates the ledstrip with `FRAME_STYLE`, see `gol_tasks::animation::animate_ledstrip`.
 ///
-/// This function listens for updates via a signal receiver carrying GameGridMessage.
-/// It animates the LED strip based on the difference between the current and previous game grid states.
-/// The `LedstripColors` struct is used to generate different colors for each step of the animation, creating a dynamic and lively
-/// effect on the LED strip.
+/// # Arguments
+///
+/// * `interval`: The interval between two updates of the game grid, see `refresh_gol_board`.
 #[embassy_executor::task]
-async fn animate_ledstrip() {
-    let ledstrip_colors = LedstripColors::new();
-    let mut gamegrid_msg: GameGridMessage = GameGridMessage {
-        game_grid_update: [false; NUM_LEDS],
-    };
-    let mut prev_gamegrid_msg;
-
-    loop {
-        // receive gg update and store last one
-        prev_gamegrid_msg = gamegrid_msg;
-        gamegrid_msg = GG_SIGNAL.wait().await;
-
-        // test purpose
-        let tmp: [u8; NUM_LEDS] = gamegrid_msg.game_grid_update.map(|v| if v { 1 } else { 0 });
-        debug!("RECEIVED update of game grid:\n\t\t{}", tmp);
-
-        // compute new colors
-        for cpt in 0..TRANSITION_STEPS {
-            let mut ledstrip_msg: LedStripMessage = LedStripMessage {
-                led_strip_update: [ColorRGB::default(); NUM_LEDS],
-            };
-            for led in 0..NUM_LEDS {
-                match (
-                    prev_gamegrid_msg.game_grid_update[led],
-                    gamegrid_msg.game_grid_update[led],
-                ) {
-                    // Alive --> Alive
-                    (true, true) => {
-                        ledstrip_msg.led_strip_update[led] =
-                            ledstrip_colors.get_color_at(cpt).current_still_alive;
-                    }
-                    // Alive --> Dead
-                    (true, false) => {
-                        ledstrip_msg.led_strip_update[led] =
-                            ledstrip_colors.get_color_at(cpt).current_alive_to_dead;
-                    }
-                    // Dead --> Alive
-                    (false, true) => {
-                        ledstrip_msg.led_strip_update[led] =
-                            ledstrip_colors.get_color_at(cpt).current_dead_to_alive;
-                    }
-                    // Dead --> Dead
-                    (false, false) => {
-                        ledstrip_msg.led_strip_update[led] =
-                            ledstrip_colors.get_color_at(cpt).current_still_dead;
-                    }
-                }
-            }
-            //signal light ledstrip
-            LED_SIGNAL.signal(ledstrip_msg);
-            Timer::after(LEDSTRIP_REFRESH_DELAY).await;
-        }
-    }
+async fn animate_ledstrip(interval: Duration) {
+    animation::animate_ledstrip(&CHANNELS, &ANIMATION_SETTINGS, interval).await
 }
 
-/// Refreshes the ws2812 LED strip with the latest color values received through the `LED_SIGNAL` embassy_sync signal.
-///
-/// This function continuously waits for new color values to be sent through the `LED_SIGNAL` broadcast channel.
-/// Once a new set of color values is received, the function lights up the LED strip with those values.
+/// Lights the ws2812 LED strip, see `gol_tasks::ledstrip::refresh_ledstrip`.
 ///
 /// # Arguments
 ///
 /// * `ws2812`: A `Ws2812` instance representing the LED strip.
 #[embassy_executor::task]
-async fn refresh_ledstrip(mut ws2812: Ws2812<PioInstanceBase<0>, SmInstanceBase<0>>) {
-    // Loop forever making RGB values and pushing them out to the WS2812.
-    loop {
-        // light ledstrip with received value
-        ws2812
-            .write(&LED_SIGNAL.wait().await.led_strip_update)
-            .await;
-    }
+async fn refresh_ledstrip(ws2812: Ws2812<PioInstanceBase<0>, SmInstanceBase<0>, LEDSTRIP_LENGTH>) {
+    ledstrip::refresh_ledstrip(&CHANNELS, &OUTPUT_SETTINGS, ws2812).await
 }
@@ -1,3 +1,6 @@
+use core::convert::Infallible;
+use core::future::Future;
+
 use cichlid::ColorRGB;
 use embassy_rp::gpio::{self};
 use embassy_rp::pio::{
@@ -5,18 +8,19 @@ use embassy_rp::pio::{
 };
 use embassy_rp::pio_instr_util;
 use embassy_rp::relocate::RelocatedProgram;
+use gol_tasks::sink::LedSink;
+use gol_tasks::smart_leds::{fill_frame, SmartLedsWriteAsync, RGB8};
 
 use {defmt_rtt as _, panic_probe as _};
 
-/// The number of LEDs in the strip
-pub(crate) const NUM_LEDS: usize = 136;
-
-/// Represents a RP2040 PIO controlled WS2812 LED strip
-pub struct Ws2812<P: PioInstance, S: SmInstance> {
+/// Represents a RP2040 PIO controlled WS2812 LED strip of `N` LEDs
+pub struct Ws2812<P: PioInstance, S: SmInstance, const N: usize> {
     sm: PioStateMachineInstance<P, S>,
+    /// The colors written through `SmartLedsWriteAsync`, kept until they are clocked out.
+    frame: [ColorRGB; N],
 }
 
-impl<P: PioInstance, S: SmInstance> Ws2812<P, S> {
+impl<P: PioInstance, S: SmInstance, const N: usize> Ws2812<P, S, N> {
     /// Creates a new WS2812 LED strip.
     ///
     /// # Arguments
@@ -30,7 +34,7 @@ impl<P: PioInstance, S: SmInstance> Ws2812<P, S> {
     /// let p = embassy_rp::init(Default::default());
     /// let (_pio0, sm0, _sm1, _sm2, _sm3) = p.PIO0.split();
     /// // Create Ledstrip
-    /// let ws2812 = Ws2812::new(sm0, p.PIN_8.degrade());
+    /// let ws2812: Ws2812<_, _, 144> = Ws2812::new(sm0, p.PIN_8.degrade());
     /// ```
     pub fn new(mut sm: PioStateMachineInstance<P, S>, pin: gpio::AnyPin) -> Self {
         // prepare the PIO program
@@ -96,40 +100,66 @@ impl<P: PioInstance, S: SmInstance> Ws2812<P, S> {
 
         sm.set_enable(true);
 
-        Self { sm }
+        Self {
+            sm,
+            frame: [ColorRGB::Black; N],
+        }
     }
 
     /// This method writes the provided sequence of RGB colors to the LED strip.
+    /// The whole strip is always clocked out, so no LED keeps a stale color.
+    ///
+    /// The driver is asynchronous: it implements `LedSink` for the tasks of `gol-tasks`, and `SmartLedsWriteAsync`
+    /// for `RGB8` iterators of the smart-leds ecosystem, wrapped in `Blocking` where a blocking `SmartLedsWrite`
+    /// is required.
     ///
     /// # Arguments
     ///
-    /// * `colors`: The sequence of RGB colors to write.
-    pub async fn write(&mut self, colors: &[ColorRGB]) {
-        for color in colors {
-            let word =
-                (u32::from(color.g) << 24) | (u32::from(color.r) << 16) | (u32::from(color.b) << 8);
+    /// * `colors`: The RGB color of every LED of the strip.
+    pub async fn write(&mut self, colors: &[ColorRGB; N]) {
+        push(&mut self.sm, colors).await
+    }
 
-            self.sm.wait_push(word).await;
-        }
+    /// Clocks out the colors written through `SmartLedsWriteAsync`.
+    async fn flush(&mut self) -> Result<(), Infallible> {
+        push(&mut self.sm, &self.frame).await;
+        Ok(())
+    }
+}
+
+/// Clocks out colors to the PIO state machine, in the GRB order of WS2812 LEDs.
+async fn push<P: PioInstance, S: SmInstance>(
+    sm: &mut PioStateMachineInstance<P, S>,
+    colors: &[ColorRGB],
+) {
+    for color in colors {
+        let word =
+            (u32::from(color.g) << 24) | (u32::from(color.r) << 16) | (u32::from(color.b) << 8);
+
+        sm.wait_push(word).await;
+    }
+}
+
+impl<P: PioInstance, S: SmInstance, const N: usize> LedSink<N> for Ws2812<P, S, N> {
+    type WriteFuture<'a> = impl Future<Output = ()> + 'a where Self: 'a;
+
+    fn write<'a>(&'a mut self, colors: &'a [ColorRGB; N]) -> Self::WriteFuture<'a> {
+        Ws2812::write(self, colors)
+    }
+}
 
-        // I missed a bit from your first post, this doesn't implement the SmartLedsWrite trait.
-        // I have a function called write that does (nearly*) the same thing,
-        // but if you're using some other crate on top, this won't meet the trait bounds. The
-        // fundamental problem is that the trait is blocking, and this is async.
-
-        // If you wanted to brute force it, you could impl the trait with block_on around the async bits.
-        // Something like this:
-        // for color in colors {
-        //     let word = (u32::from(color.g) << 24) | (u32::from(color.r) << 16) | (u32::from(color.b) << 8);
-        //     block_on(self.sm.wait_push(word).await);
-        // }
-
-        // In that case, if the only thing you're doing is rendering something and writing it to a string of ws2812s,
-        // then you'll only block if the render is faster than the pio.
-        // Of course if you're doing other tasks then you'll also stall them whenever the renderer outpaces the pio.
-        // A workaround in the case of the 2040 would be to make use of the second core to handle rendering.
-
-        // The better path forward would be to introduce an async version of the SmartLedsWrite trait.
-        // The downside being the function color problem meaning that anything using said trait also needs to be async.
+impl<P: PioInstance, S: SmInstance, const N: usize> SmartLedsWriteAsync for Ws2812<P, S, N> {
+    type Error = Infallible;
+    type Color = RGB8;
+    type WriteFuture<'a> = impl Future<Output = Result<(), Infallible>> + 'a where Self: 'a;
+
+    fn write<'a, T, I>(&'a mut self, iterator: T) -> Self::WriteFuture<'a>
+    where
+        T: IntoIterator<Item = I>,
+        I: Into<Self::Color>,
+    {
+        fill_frame(&mut self.frame, iterator);
+        // The future must not depend on `T` nor `I`, so it is built by a method that is not generic over them.
+        self.flush()
     }
 }